@@ -1,13 +1,41 @@
+use advent_of_code::template::stress::Rng;
 use itertools::Itertools;
-advent_of_code::solution!(1);
+advent_of_code::solution!(1, generator: generate);
 
-pub fn part_one(input: &str) -> Option<u32> {
+/// Parses the two columns of location IDs. Either column may be blank on a line, so the lists
+/// can have different lengths.
+fn parse(input: &str) -> (Vec<u32>, Vec<u32>) {
     let mut list_one: Vec<u32> = Vec::new();
     let mut list_two: Vec<u32> = Vec::new();
-    input.split_whitespace().tuples().for_each(|(x, y)| {
-        list_one.push(x.parse().unwrap());
-        list_two.push(y.parse().unwrap())
-    });
+    for line in input.lines() {
+        let (x, y) = line.split_once(' ').unwrap_or((line, ""));
+        list_one.extend(x.trim().parse::<u32>().ok());
+        list_two.extend(y.trim().parse::<u32>().ok());
+    }
+    (list_one, list_two)
+}
+
+/// Two lists of `size` IDs with many duplicates, where some lines lack one of the IDs.
+fn generate(seed: u64, size: usize) -> String {
+    let mut rng = Rng::new(seed);
+    let ids = (0..size.max(1))
+        .map(|_| rng.range(10_000..100_000))
+        .collect_vec();
+    (0..size)
+        .map(|_| {
+            let x = rng.choose(&ids).to_string();
+            let y = rng.choose(&ids).to_string();
+            match rng.range(0..10) {
+                0 => format!("{x}      "),
+                1 => format!("      {y}"),
+                _ => format!("{x}   {y}"),
+            }
+        })
+        .join("\n")
+}
+
+pub fn part_one(input: &str) -> Option<u32> {
+    let (mut list_one, mut list_two) = parse(input);
     list_two.sort();
     list_one.sort();
     Some(
@@ -20,12 +48,7 @@ pub fn part_one(input: &str) -> Option<u32> {
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let mut list_one: Vec<u32> = Vec::new();
-    let mut list_two: Vec<u32> = Vec::new();
-    input.split_whitespace().tuples().for_each(|(x, y)| {
-        list_one.push(x.parse().unwrap());
-        list_two.push(y.parse().unwrap())
-    });
+    let (list_one, list_two) = parse(input);
 
     let counts = list_two.iter().counts();
 
@@ -44,6 +67,14 @@ pub fn part_two(input: &str) -> Option<u32> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_mismatched_lists() {
+        let (list_one, list_two) = parse("3   4\n4      \n      9\n1   3");
+        assert_eq!(list_one, vec![3, 4, 1]);
+        assert_eq!(list_two, vec![4, 9, 3]);
+        assert_eq!(parse("1   2\n3").1, vec![2]);
+    }
+
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
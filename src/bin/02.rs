@@ -1,70 +1,213 @@
+use advent_of_code::template::stress::Rng;
 use itertools::Itertools;
 
-advent_of_code::solution!(2);
+advent_of_code::solution!(2, modes: {
+    "--explain" => explain,
+}, generator: generate, variants: {
+    2 => part_two_brute_force,
+});
 
-pub fn evaluate(levels: &[u32]) -> bool {
-    let (safe, dir): (Vec<bool>, Vec<bool>) = levels
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rule {
+    /// Adjacent levels differ by less than one or more than three.
+    Gap(u32),
+    /// The levels change direction.
+    Direction,
+}
+
+/// The first pair of adjacent levels of a report that breaks a rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Violation {
+    /// The index of the first level of the pair; the second one follows it directly.
+    pub index: usize,
+    pub rule: Rule,
+}
+
+/// Finds the first violation in `levels`, as if the level at `skip` had been removed.
+fn first_violation(levels: &[u32], skip: Option<usize>) -> Option<Violation> {
+    let mut increasing = None;
+    levels
         .iter()
+        .enumerate()
+        .filter(|(i, _)| Some(*i) != skip)
         .tuple_windows()
-        .map(|(x, y)| check_safe(x, y))
-        .collect();
-    let all_safe = safe.iter().all(|b| *b);
-    let all_same_dir = dir.iter().all_equal();
-    all_safe && all_same_dir
+        .find_map(|((index, x), (_, y))| {
+            let difference = x.abs_diff(*y);
+            let rule = if !(1..=3).contains(&difference) {
+                Rule::Gap(difference)
+            } else if *increasing.get_or_insert(x < y) != (x < y) {
+                Rule::Direction
+            } else {
+                return None;
+            };
+            Some(Violation { index, rule })
+        })
+}
+
+pub fn evaluate(levels: &[u32]) -> bool {
+    first_violation(levels, None).is_none()
+}
+
+/// Checks a report with the Problem Dampener.
+///
+/// Returns `Ok(None)` if the report is safe as is, `Ok(Some(i))` if removing level `i` makes it
+/// safe and the first violation otherwise. Only the levels around the first violation can fix it:
+/// the two levels of the pair, and the one before them since it decided the direction.
+pub fn dampen(levels: &[u32]) -> Result<Option<usize>, Violation> {
+    let Some(violation) = first_violation(levels, None) else {
+        return Ok(None);
+    };
+    let i = violation.index;
+    i.checked_sub(1)
+        .into_iter()
+        .chain([i, i + 1])
+        .find(|skip| first_violation(levels, Some(*skip)).is_none())
+        .map(Some)
+        .ok_or(violation)
+}
+
+fn parse(input: &str) -> Vec<Vec<u32>> {
+    input
+        .lines()
+        .map(|line| {
+            line.split_whitespace()
+                .map(|c| c.parse::<u32>().unwrap())
+                .collect_vec()
+        })
+        .collect()
 }
 
-pub fn check_safe(x: &u32, y: &u32) -> (bool, bool) {
-    let is_increasing = x < y;
-    let difference = x.abs_diff(*y);
-    let diff_ok = (1..=3).contains(&difference);
-    (diff_ok, is_increasing)
+/// `size` reports that mostly change steadily, with a few bad levels mixed in.
+fn generate(seed: u64, size: usize) -> String {
+    let mut rng = Rng::new(seed);
+    (0..size)
+        .map(|_| {
+            let direction = if rng.chance(0.5) { 1 } else { -1 };
+            let mut level = rng.range(20..80) as i32;
+            let mut levels = vec![level];
+            for _ in 1..rng.range(5..9) {
+                level += if rng.chance(0.15) {
+                    rng.range(0..9) as i32 - 4
+                } else {
+                    direction * rng.range(1..4) as i32
+                };
+                levels.push(level);
+            }
+            levels.iter().join(" ")
+        })
+        .join("\n")
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
     Some(
-        input
-            .lines()
-            .filter_map(|line| {
-                let levels = line
-                    .split_whitespace()
-                    .map(|c| c.parse::<u32>().unwrap())
-                    .collect_vec();
-                evaluate(&levels).then_some(())
-            })
+        parse(input)
+            .iter()
+            .filter(|levels| evaluate(levels))
             .count() as u32,
     )
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let r = input
-        .lines()
-        .filter_map(|line| {
-            let levels = line
-                .split_whitespace()
-                .map(|c| c.parse::<u32>().unwrap())
-                .collect_vec();
-            let all_safe = evaluate(&levels);
-            if all_safe {
-                return Some(());
-            }
-            for p in 0..levels.len() {
-                //todo: check only problem indexes
-                let mut levels = levels.clone();
-                levels.remove(p);
-                if evaluate(&levels) {
-                    return Some(());
-                }
-            }
-            None
+    Some(
+        parse(input)
+            .iter()
+            .filter(|levels| dampen(levels).is_ok())
+            .count() as u32,
+    )
+}
+
+/// Checks a report with the Problem Dampener by trying to remove every level in turn.
+fn dampen_brute_force(levels: &[u32]) -> bool {
+    evaluate(levels)
+        || (0..levels.len()).any(|i| {
+            let mut levels = levels.to_vec();
+            levels.remove(i);
+            evaluate(&levels)
         })
-        .count();
-    Some(r as u32)
+}
+
+pub fn part_two_brute_force(input: &str) -> Option<u32> {
+    Some(
+        parse(input)
+            .iter()
+            .filter(|levels| dampen_brute_force(levels))
+            .count() as u32,
+    )
+}
+
+fn explain_report(levels: &[u32]) -> Option<String> {
+    let violation = first_violation(levels, None)?;
+    let (x, y) = (levels[violation.index], levels[violation.index + 1]);
+    let rule = match violation.rule {
+        Rule::Gap(difference) => format!("{x} -> {y} changes by {difference}"),
+        Rule::Direction => format!("{x} -> {y} changes direction"),
+    };
+    let fix = match dampen(levels) {
+        Ok(Some(i)) => format!("safe without level {} ({})", i + 1, levels[i]),
+        _ => "unsafe with any level removed".to_string(),
+    };
+    Some(format!("{}: {rule}; {fix}", levels.iter().join(" ")))
+}
+
+/// Prints the broken rule and the fix of every unsafe report.
+fn explain(input: &str) {
+    for levels in parse(input) {
+        if let Some(explanation) = explain_report(&levels) {
+            println!("{explanation}");
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_dampen() {
+        assert_eq!(dampen(&[7, 6, 4, 2, 1]), Ok(None));
+        assert_eq!(dampen(&[1, 3, 2, 4, 5]), Ok(Some(1)));
+        assert_eq!(dampen(&[8, 6, 4, 4, 1]), Ok(Some(2)));
+        // removing the first level changes the direction the others are checked against
+        assert_eq!(dampen(&[5, 6, 4, 3, 2]), Ok(Some(0)));
+        assert_eq!(dampen(&[1, 2, 3, 4, 9]), Ok(Some(4)));
+        assert_eq!(
+            dampen(&[1, 2, 7, 8, 9]),
+            Err(Violation {
+                index: 1,
+                rule: Rule::Gap(5)
+            })
+        );
+    }
+
+    #[test]
+    fn test_dampen_matches_brute_force() {
+        for levels in (0..5).map(|_| 1..=5).multi_cartesian_product() {
+            assert_eq!(
+                dampen(&levels).is_ok(),
+                dampen_brute_force(&levels),
+                "{levels:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_explain() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let explained = parse(&input)
+            .iter()
+            .filter_map(|levels| explain_report(levels))
+            .collect_vec();
+        assert_eq!(
+            explained,
+            vec![
+                "1 2 7 8 9: 2 -> 7 changes by 5; unsafe with any level removed",
+                "9 7 6 2 1: 6 -> 2 changes by 4; unsafe with any level removed",
+                "1 3 2 4 5: 3 -> 2 changes direction; safe without level 2 (3)",
+                "8 6 4 4 1: 4 -> 4 changes by 0; safe without level 3 (4)",
+            ]
+        );
+    }
+
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
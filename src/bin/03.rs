@@ -1,55 +1,179 @@
-use regex::Regex;
-
 advent_of_code::solution!(3);
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Instruction {
+    Mul(u32, u32),
+    Do,
+    Dont,
+}
+
+impl Instruction {
+    /// The names the tokenizer looks for in the corrupted memory.
+    const NAMES: [&'static str; 3] = ["mul", "do", "don't"];
+
+    /// The instruction called `name` with `args`, if that is a valid instruction.
+    fn new(name: &str, args: &[u32]) -> Option<Self> {
+        match (name, args) {
+            ("mul", [x, y]) => Some(Instruction::Mul(*x, *y)),
+            ("do", []) => Some(Instruction::Do),
+            ("don't", []) => Some(Instruction::Dont),
+            _ => None,
+        }
+    }
+}
+
+/// Parses `(arg, ...)` at the start of `memory`, where every argument has one to three digits.
+///
+/// Returns the arguments and the length of the call.
+fn parse_args(memory: &[u8]) -> Option<(Vec<u32>, usize)> {
+    let mut args = vec![];
+    let mut pos = 1;
+    if memory.first() != Some(&b'(') {
+        return None;
+    }
+    loop {
+        let digits = memory[pos..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit())
+            .count();
+        if digits > 3 || (digits == 0 && !args.is_empty()) {
+            return None;
+        }
+        if digits > 0 {
+            let arg = memory[pos..pos + digits]
+                .iter()
+                .fold(0, |n, b| n * 10 + (b - b'0') as u32);
+            args.push(arg);
+            pos += digits;
+        }
+        match memory.get(pos) {
+            Some(b')') => return Some((args, pos + 1)),
+            Some(b',') if digits > 0 => pos += 1,
+            _ => return None,
+        }
+    }
+}
+
+/// Yields the valid instructions of a corrupted memory in order, skipping everything else.
+struct Tokenizer<'a> {
+    memory: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Tokenizer<'a> {
+    fn new(memory: &'a str) -> Self {
+        Tokenizer {
+            memory: memory.as_bytes(),
+            pos: 0,
+        }
+    }
+}
+
+impl Iterator for Tokenizer<'_> {
+    type Item = Instruction;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pos < self.memory.len() {
+            let rest = &self.memory[self.pos..];
+            let token = Instruction::NAMES.iter().find_map(|name| {
+                let call = rest.strip_prefix(name.as_bytes())?;
+                let (args, len) = parse_args(call)?;
+                Some((Instruction::new(name, &args)?, name.len() + len))
+            });
+            match token {
+                Some((instruction, len)) => {
+                    self.pos += len;
+                    return Some(instruction);
+                }
+                None => self.pos += 1,
+            }
+        }
+        None
+    }
+}
+
+struct State {
+    enabled: bool,
+    sum: u32,
+}
+
+/// Reacts to the instructions it knows and ignores all others.
+type Handler = fn(&mut State, Instruction);
+
+fn multiply(state: &mut State, instruction: Instruction) {
+    if let Instruction::Mul(x, y) = instruction {
+        if state.enabled {
+            state.sum += x * y;
+        }
+    }
+}
+
+fn conditionals(state: &mut State, instruction: Instruction) {
+    match instruction {
+        Instruction::Do => state.enabled = true,
+        Instruction::Dont => state.enabled = false,
+        _ => {}
+    }
+}
+
+/// Runs instructions through every handler in turn.
+struct Interpreter {
+    handlers: Vec<Handler>,
+}
+
+impl Interpreter {
+    fn new(handlers: &[Handler]) -> Self {
+        Interpreter {
+            handlers: handlers.to_vec(),
+        }
+    }
+
+    fn run(&self, instructions: impl Iterator<Item = Instruction>) -> State {
+        let mut state = State {
+            enabled: true,
+            sum: 0,
+        };
+        for instruction in instructions {
+            for handler in &self.handlers {
+                handler(&mut state, instruction);
+            }
+        }
+        state
+    }
+}
+
 pub fn part_one(input: &str) -> Option<u32> {
-    let regex = Regex::new(r"mul\((\d+),(\d+)\)").unwrap();
-    Some(
-        regex
-            .captures_iter(input)
-            .map(|c| c.extract())
-            .map(|(_, [x, y])| {
-                let x: u32 = x.parse().unwrap();
-                let y: u32 = y.parse().unwrap();
-                x * y
-            })
-            .sum(),
-    )
+    let interpreter = Interpreter::new(&[multiply]);
+    Some(interpreter.run(Tokenizer::new(input)).sum)
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let regex =
-        Regex::new(r"(?<mul>mul\((?<x>\d+),(?<y>\d+)\))|(?<do>do\(\))|(?<dont>don't\(\))").unwrap();
-    let mut mul_enable = true;
-    Some(
-        regex
-            .captures_iter(input)
-            .flat_map(|c| {
-                if c.name("mul").is_some() {
-                    if mul_enable {
-                        let x: u32 = c["x"].parse().unwrap();
-                        let y: u32 = c["y"].parse().unwrap();
-                        Some(x * y)
-                    } else {
-                        None
-                    }
-                } else if c.name("do").is_some() {
-                    mul_enable = true;
-                    None
-                } else if c.name("dont").is_some() {
-                    mul_enable = false;
-                    None
-                } else {
-                    panic!()
-                }
-            })
-            .sum(),
-    )
+    let interpreter = Interpreter::new(&[multiply, conditionals]);
+    Some(interpreter.run(Tokenizer::new(input)).sum)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use Instruction::*;
+
+    #[test]
+    fn test_tokenizer() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let instructions: Vec<_> = Tokenizer::new(&input).collect();
+        assert_eq!(
+            instructions,
+            vec![Mul(2, 4), Dont, Mul(5, 5), Mul(11, 8), Do, Mul(8, 5)]
+        );
+    }
+
+    #[test]
+    fn test_tokenizer_rejects_malformed_calls() {
+        let memory =
+            "mul(4*mul(6,9!?(12,34)mul ( 2 , 4 )mul(1234,5)mul(,1)mul(1,)do(1)mul(mul(1,2)";
+        let instructions: Vec<_> = Tokenizer::new(memory).collect();
+        assert_eq!(instructions, vec![Mul(1, 2)]);
+    }
 
     #[test]
     fn test_part_one() {
@@ -1,136 +1,254 @@
+use advent_of_code::template::stress::Rng;
 use itertools::Itertools;
-use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
 
-advent_of_code::solution!(5);
+advent_of_code::solution!(5, generator: generate);
 
-#[derive(Debug)]
-struct Rule {
-    pub before: u32,
-    pub after: u32,
-}
-impl Rule {
-    fn parse(input: &str) -> Rule {
-        let (before, after) = input
-            .split("|")
-            .map(|s| s.trim().parse().unwrap())
-            .collect_tuple()
-            .unwrap();
-        Rule { before, after }
-    }
+/// The page ordering rules, as a graph from each page to the pages that must come after it.
+struct Rules {
+    after: HashMap<u32, HashSet<u32>>,
 }
 
-#[derive(PartialEq, Eq)]
-struct Page {
-    number: u32,
-    must_be_before: Vec<u32>,
+#[derive(Debug, PartialEq, Eq)]
+enum OrderError {
+    /// The rules between the pages of the update contain a cycle: each of these pages must come
+    /// before the next one, and the last one before the first.
+    Cycle(Vec<u32>),
+    /// No rule decides which of these two pages comes first.
+    Ambiguous(u32, u32),
 }
 
-impl PartialEq<u32> for Page {
-    fn eq(&self, other: &u32) -> bool {
-        self.number == *other
+impl std::fmt::Display for OrderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OrderError::Cycle(pages) => {
+                write!(f, "the rules contain a cycle among pages {pages:?}")
+            }
+            OrderError::Ambiguous(a, b) => {
+                write!(f, "no rule orders pages {a} and {b}")
+            }
+        }
     }
 }
 
-impl PartialOrd for Page {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+impl Rules {
+    fn parse(input: &str) -> Option<Self> {
+        let mut after: HashMap<u32, HashSet<u32>> = HashMap::new();
+        for line in input.lines() {
+            let (before, page) = line.split_once('|')?;
+            after
+                .entry(before.trim().parse().ok()?)
+                .or_default()
+                .insert(page.trim().parse().ok()?);
+        }
+        Some(Rules { after })
+    }
+
+    fn must_precede(&self, before: u32, after: u32) -> bool {
+        self.after.get(&before).is_some_and(|a| a.contains(&after))
+    }
+
+    /// Whether no rule is broken by the order of `update`.
+    fn is_ordered(&self, update: &[u32]) -> bool {
+        update
+            .iter()
+            .tuple_combinations()
+            .all(|(a, b)| !self.must_precede(*b, *a))
+    }
+
+    /// The only order of the pages of `update` that follows the rules between them.
+    ///
+    /// This is a topological sort of the rule graph restricted to the update's pages, which
+    /// fails if that graph has a cycle or more than one topological order.
+    fn order(&self, update: &[u32]) -> Result<Vec<u32>, OrderError> {
+        let pages: HashSet<u32> = update.iter().copied().collect();
+        let successors = |page: u32| {
+            self.after
+                .get(&page)
+                .into_iter()
+                .flatten()
+                .filter(|p| pages.contains(p))
+                .copied()
+        };
+        let mut in_degree: HashMap<u32, usize> = pages.iter().map(|p| (*p, 0)).collect();
+        for page in &pages {
+            for next in successors(*page) {
+                *in_degree.get_mut(&next).unwrap() += 1;
+            }
+        }
+
+        let mut ready = update
+            .iter()
+            .copied()
+            .filter(|p| in_degree[p] == 0)
+            .unique()
+            .collect_vec();
+        let mut order = Vec::with_capacity(pages.len());
+        while let Some(page) = ready.pop() {
+            if let Some(other) = ready.first() {
+                return Err(OrderError::Ambiguous(*other, page));
+            }
+            order.push(page);
+            for next in successors(page) {
+                let degree = in_degree.get_mut(&next).unwrap();
+                *degree -= 1;
+                if *degree == 0 {
+                    ready.push(next);
+                }
+            }
+        }
+
+        if order.len() < pages.len() {
+            let remaining = update
+                .iter()
+                .copied()
+                .filter(|p| !order.contains(p))
+                .unique()
+                .collect_vec();
+            return Err(OrderError::Cycle(self.find_cycle(&remaining)));
+        }
+        Ok(order)
+    }
+
+    /// A cycle among the pages the topological sort couldn't place, in the order of the rules and
+    /// starting with the page that comes first in `remaining`.
+    ///
+    /// Each of these pages still has a predecessor among them, so following the predecessors
+    /// from any page has to repeat a page, and the pages in between form a cycle.
+    fn find_cycle(&self, remaining: &[u32]) -> Vec<u32> {
+        let mut path = vec![remaining[0]];
+        loop {
+            let page = *path.last().unwrap();
+            let previous = *remaining
+                .iter()
+                .find(|p| self.must_precede(**p, page))
+                .expect("every remaining page has a remaining predecessor");
+            if let Some(start) = path.iter().position(|p| *p == previous) {
+                let mut cycle = path.split_off(start);
+                cycle.reverse();
+                let first = cycle
+                    .iter()
+                    .position_min_by_key(|p| remaining.iter().position(|r| r == *p))
+                    .unwrap();
+                cycle.rotate_left(first);
+                return cycle;
+            }
+            path.push(previous);
+        }
     }
 }
 
-impl Ord for Page {
-    fn cmp(&self, other: &Self) -> Ordering {
-        if self.must_be_before.iter().any(|r| *r == other.number) {
-            Ordering::Less
-        } else {
-            Ordering::Greater
+fn parse(input: &str) -> (Rules, Vec<Vec<u32>>) {
+    let (rules, updates) = input.split_once("\n\n").unwrap();
+    let rules = Rules::parse(rules).unwrap();
+    let updates = updates
+        .lines()
+        .map(|line| line.split(',').map(|n| n.parse().unwrap()).collect())
+        .collect();
+    (rules, updates)
+}
+
+/// Rules between `size` pages and `size` updates of them.
+///
+/// The rules mostly follow one order of the pages, but skip some pairs, reverse a few, and leave
+/// some pages without any rule at all.
+fn generate(seed: u64, size: usize) -> String {
+    let mut rng = Rng::new(seed);
+    let mut pages = (10..100).collect_vec();
+    rng.shuffle(&mut pages);
+    pages.truncate(size.clamp(3, 90));
+    let unrelated = rng.range(0..pages.len() / 3 + 1);
+    let ruled = &pages[unrelated..];
+
+    let mut rules = vec![];
+    for (i, a) in ruled.iter().enumerate() {
+        for b in &ruled[i + 1..] {
+            match rng.range(0..20) {
+                0 => rules.push(format!("{b}|{a}")),
+                1..=3 => {}
+                _ => rules.push(format!("{a}|{b}")),
+            }
         }
     }
+    rng.shuffle(&mut rules);
+
+    let updates = (0..size.max(1)).map(|_| {
+        let len = rng.range(0..pages.len().div_ceil(2)) * 2 + 1;
+        let mut update = pages.clone();
+        rng.shuffle(&mut update);
+        update[..len].iter().join(",")
+    });
+    rules
+        .into_iter()
+        .chain([String::new()])
+        .chain(updates)
+        .join("\n")
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
-    let (rules, manuals) = input
-        .split("\n\n")
-        .map(|s| s.split_whitespace().collect_vec())
-        .collect_tuple()
-        .unwrap();
-    let rules = rules.into_iter().map(Rule::parse).collect_vec();
-    let manuals: Vec<Vec<u32>> = manuals
+    let (rules, updates) = parse(input);
+    let sum = updates
         .iter()
-        .map(|s| s.split(",").map(|s| s.parse().unwrap()).collect_vec())
-        .collect_vec();
-    Some(
-        manuals
-            .into_iter()
-            .filter_map(|page_list| {
-                // println!("page list: {page_list:?}");
-                let relevant_rules = rules
-                    .iter()
-                    .filter(|r| page_list.contains(&r.before) && page_list.contains(&r.after))
-                    .collect_vec();
-                let page_correct = page_list
-                    .iter()
-                    .enumerate()
-                    .flat_map(|(index, page)| {
-                        // println!("checking page {page} ({index}");
-                        relevant_rules
-                            .iter()
-                            .filter(|r| r.before == *page)
-                            .map(|r| {
-                                let other_page_index =
-                                    page_list.iter().position(|p| p == &r.after).unwrap();
-                                // println!("{index}, {other_page_index} {}", index <= other_page_index);
-                                index <= other_page_index
-                            })
-                            .collect_vec()
-                    })
-                    .all(|b| b);
-                if page_correct {
-                    Some(page_list[page_list.len() / 2])
-                } else {
-                    None
-                }
-            })
-            .sum(),
-    )
+        .filter(|update| rules.is_ordered(update))
+        .map(|update| update[update.len() / 2])
+        .sum();
+    Some(sum)
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let (rules, manuals) = input
-        .split("\n\n")
-        .map(|s| s.split_whitespace().collect_vec())
-        .collect_tuple()
-        .unwrap();
-    let rules = rules.into_iter().map(Rule::parse).collect_vec();
-    let manuals: Vec<Vec<u32>> = manuals
-        .iter()
-        .map(|s| s.split(",").map(|s| s.parse().unwrap()).collect_vec())
-        .collect_vec();
-    Some(
-        manuals
-            .into_iter()
-            .filter_map(|page_list| {
-                let mut page_list_sorted = page_list
-                    .iter()
-                    .map(|page| Page {
-                        number: *page,
-                        must_be_before: rules
-                            .iter()
-                            .filter_map(|r| (&r.before == page).then_some(r.after))
-                            .collect(),
-                    })
-                    .collect_vec();
-                page_list_sorted.sort();
-                let changes = page_list_sorted != page_list;
-                changes.then_some(page_list_sorted[page_list_sorted.len() / 2].number)
-            })
-            .sum(),
-    )
+    let (rules, updates) = parse(input);
+    let mut sum = 0;
+    for (i, update) in updates.iter().enumerate() {
+        if rules.is_ordered(update) {
+            continue;
+        }
+        match rules.order(update) {
+            Ok(order) => sum += order[order.len() / 2],
+            Err(e) => {
+                eprintln!("update {}: {e}", i + 1);
+                return None;
+            }
+        }
+    }
+    Some(sum)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_order() {
+        let (rules, _) = parse(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(
+            rules.order(&[75, 97, 47, 61, 53]),
+            Ok(vec![97, 75, 47, 61, 53])
+        );
+        assert_eq!(rules.order(&[61, 13, 29]), Ok(vec![61, 29, 13]));
+        assert_eq!(
+            rules.order(&[97, 13, 75, 29, 47]),
+            Ok(vec![97, 75, 47, 29, 13])
+        );
+    }
+
+    #[test]
+    fn test_order_errors() {
+        let rules = Rules::parse("1|2\n2|3\n3|1\n4|5").unwrap();
+        assert_eq!(
+            rules.order(&[1, 2, 3]),
+            Err(OrderError::Cycle(vec![1, 2, 3]))
+        );
+        assert_eq!(rules.order(&[4, 1, 5]), Err(OrderError::Ambiguous(4, 1)));
+        assert_eq!(rules.order(&[5, 4]), Ok(vec![4, 5]));
+        // the cycle doesn't matter when the update only contains part of it
+        assert_eq!(rules.order(&[3, 2]), Ok(vec![2, 3]));
+
+        // 3 can't be placed either, but only depends on the cycle.
+        let rules = Rules::parse("1|2\n2|1\n2|3").unwrap();
+        assert_eq!(rules.order(&[1, 2, 3]), Err(OrderError::Cycle(vec![1, 2])));
+        assert_eq!(rules.order(&[3, 2, 1]), Err(OrderError::Cycle(vec![2, 1])));
+    }
+
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
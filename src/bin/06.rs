@@ -1,7 +1,8 @@
-use std::ops::{Not, Range};
+use std::ops::Range;
 
 advent_of_code::solution!(6);
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum Direction {
     Up,
     Right,
@@ -47,6 +48,116 @@ impl Direction {
             Left => Up,
         }
     }
+
+    /// A distinct bit per direction, used to record which headings a cell was visited with.
+    fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+/// The position and heading of the guard.
+type State = ((usize, usize), Direction);
+
+/// How a patrol ends.
+#[derive(Debug, PartialEq)]
+enum Patrol {
+    /// The guard walks off the map after visiting this many distinct positions.
+    Exits(usize),
+    /// The guard returns to a state it has been in before and never leaves.
+    Loops,
+}
+
+struct Lab {
+    width: usize,
+    height: usize,
+    obstructions: Vec<bool>,
+    guard: State,
+}
+
+impl Lab {
+    fn parse(input: &str) -> Self {
+        let width = input.lines().next().unwrap().len();
+        let height = input.lines().count();
+        let bytes = input.lines().flat_map(str::bytes);
+        let obstructions = bytes.clone().map(|b| b == b'#').collect();
+        let guard_index = bytes.clone().position(|b| b"^>v<".contains(&b)).unwrap();
+        let guard_byte = bytes.clone().nth(guard_index).unwrap();
+        Lab {
+            width,
+            height,
+            obstructions,
+            guard: (
+                index_2_cood(guard_index, width),
+                Direction::from_byte(guard_byte),
+            ),
+        }
+    }
+
+    /// The state after the guard's next move, or `None` if it leaves the map.
+    fn step(&self, (c, dir): State, extra_obstruction: Option<(usize, usize)>) -> Option<State> {
+        let x_range = 0..self.width as i32;
+        let y_range = 0..self.height as i32;
+        let next = dir.move_in(c, &x_range, &y_range)?;
+        if self.obstructions[cood_2_index(next, self.width)] || Some(next) == extra_obstruction {
+            Some((c, dir.rotate()))
+        } else {
+            Some((next, dir))
+        }
+    }
+
+    /// Iterates over every state of the guard's patrol, starting with its initial state.
+    ///
+    /// The iterator never ends if the patrol loops, see [`Lab::patrol`].
+    fn walk(&self) -> impl Iterator<Item = State> + '_ {
+        std::iter::successors(Some(self.guard), |state| self.step(*state, None))
+    }
+
+    /// Simulates the patrol from `start`, optionally with an additional obstruction.
+    ///
+    /// A loop is detected once the guard is in a position it has already been in with the same
+    /// heading.
+    fn patrol(&self, start: State, extra_obstruction: Option<(usize, usize)>) -> Patrol {
+        let mut seen = vec![0u8; self.obstructions.len()];
+        let mut state = Some(start);
+        while let Some((c, dir)) = state {
+            let seen = &mut seen[cood_2_index(c, self.width)];
+            if *seen & dir.bit() != 0 {
+                return Patrol::Loops;
+            }
+            *seen |= dir.bit();
+            state = self.step((c, dir), extra_obstruction);
+        }
+        Patrol::Exits(seen.iter().filter(|s| **s != 0).count())
+    }
+
+    /// The distinct positions on the guard's original path, each with the state the guard is in
+    /// right before first stepping onto it.
+    fn path_with_approach(&self) -> Vec<((usize, usize), State)> {
+        let mut first_visit = vec![false; self.obstructions.len()];
+        first_visit[cood_2_index(self.guard.0, self.width)] = true;
+        self.walk()
+            .zip(self.walk().skip(1))
+            .filter_map(|(previous, (c, _))| {
+                let first_visit = &mut first_visit[cood_2_index(c, self.width)];
+                (!std::mem::replace(first_visit, true)).then_some((c, previous))
+            })
+            .collect()
+    }
+
+    /// Positions where a single new obstruction traps the guard in a loop.
+    ///
+    /// Only positions on the original path can change the patrol. The patrol up to the first
+    /// visit of such a position is unaffected by an obstruction there, so each candidate is
+    /// simulated from the state right before it.
+    ///
+    /// Never returns if the original patrol already loops.
+    fn loop_obstructions(&self) -> Vec<(usize, usize)> {
+        self.path_with_approach()
+            .into_iter()
+            .filter(|(c, approach)| self.patrol(*approach, Some(*c)) == Patrol::Loops)
+            .map(|(c, _)| c)
+            .collect()
+    }
 }
 
 fn index_2_cood(index: usize, line_len: usize) -> (usize, usize) {
@@ -55,38 +166,52 @@ fn index_2_cood(index: usize, line_len: usize) -> (usize, usize) {
 fn cood_2_index(c: (usize, usize), line_len: usize) -> usize {
     c.1 * line_len + c.0
 }
+
 pub fn part_one(input: &str) -> Option<u32> {
-    let line_count = input.split_whitespace().count();
-    let line_len = input.find("\n").unwrap() + 1;
-    let x_range = 0..line_len as i32;
-    let y_range = 0..line_count as i32;
-    let gaurd_index = input.find("^").unwrap();
-    let mut gaurd_cood = index_2_cood(gaurd_index, line_len);
-    let mut dir = Direction::from_byte(input.as_bytes()[gaurd_index]);
-    let mut visited = vec![gaurd_cood];
-    while let Some(c) = dir.move_in(gaurd_cood, &x_range, &y_range) {
-        match input.as_bytes()[cood_2_index(c, line_len)] {
-            b'#' => dir = dir.rotate(),
-            _ => {
-                if visited.contains(&c).not() {
-                    visited.push(c);
-                }
-                gaurd_cood = c
-            }
-        }
+    let lab = Lab::parse(input);
+    match lab.patrol(lab.guard, None) {
+        Patrol::Exits(visited) => Some(visited as u32),
+        Patrol::Loops => None,
     }
-
-    Some(visited.len() as u32)
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    None
+    let lab = Lab::parse(input);
+    if lab.patrol(lab.guard, None) == Patrol::Loops {
+        return None;
+    }
+    Some(lab.loop_obstructions().len() as u32)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_loop_obstructions() {
+        let lab = Lab::parse(&advent_of_code::template::read_file("examples", DAY));
+        let mut obstructions = lab.loop_obstructions();
+        obstructions.sort();
+        assert_eq!(
+            obstructions,
+            vec![(1, 8), (3, 6), (3, 8), (6, 7), (7, 7), (7, 9)]
+        );
+        assert_eq!(lab.patrol(lab.guard, Some((3, 6))), Patrol::Loops);
+    }
+
+    #[test]
+    fn test_exits_to_the_right() {
+        let lab = Lab::parse("..#\n.>.\n...\n");
+        assert_eq!(lab.patrol(lab.guard, None), Patrol::Exits(2));
+    }
+
+    #[test]
+    fn test_original_patrol_loops() {
+        let input = ".#..\n.^.#\n#...\n..#.\n";
+        assert_eq!(part_one(input), None);
+        assert_eq!(part_two(input), None);
+    }
+
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
@@ -96,6 +221,6 @@ mod tests {
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result, Some(6));
     }
 }
@@ -1,88 +1,224 @@
 use itertools::Itertools;
 
-advent_of_code::solution!(7);
+advent_of_code::solution!(7, modes: {
+    "--explain" => explain,
+});
 
-#[derive(Debug, Clone, Copy)]
+/// An operator that can be placed between two numbers of an equation.
+///
+/// New operators need both a [`compute`](Operation::compute) and its inverse
+/// [`undo`](Operation::undo), which the solver uses to work backwards from the test value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Operation {
     Add,
     Mult,
     Concat,
 }
 
+const PART_ONE_OPERATIONS: [Operation; 2] = [Operation::Add, Operation::Mult];
+const PART_TWO_OPERATIONS: [Operation; 3] = [Operation::Add, Operation::Mult, Operation::Concat];
+
+/// The power of ten that shifts a number left by the digits of `n`.
+fn digit_shift(n: u64) -> u64 {
+    10u64.pow(n.checked_ilog10().unwrap_or(0) + 1)
+}
+
 impl Operation {
-    fn compute(&self, a: u64, b: u64) -> u64 {
+    /// The result of `a <op> b`, or `None` if it overflows.
+    fn compute(&self, a: u64, b: u64) -> Option<u64> {
+        match self {
+            Operation::Add => a.checked_add(b),
+            Operation::Mult => a.checked_mul(b),
+            Operation::Concat => a.checked_mul(digit_shift(b))?.checked_add(b),
+        }
+    }
+
+    /// The left operand `a` such that `a <op> b == result`, if there is one.
+    ///
+    /// Assumes `b` is not zero, as every number in the puzzle input is positive.
+    fn undo(&self, result: u64, b: u64) -> Option<u64> {
+        match self {
+            Operation::Add => result.checked_sub(b),
+            Operation::Mult => (b != 0 && result.is_multiple_of(b)).then(|| result / b),
+            Operation::Concat => {
+                let shift = digit_shift(b);
+                (result % shift == b).then(|| result / shift)
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Operation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Operation::Add => a + b,
-            Operation::Mult => a * b,
-            Operation::Concat => a * 10u64.pow(b.ilog10() + 1) + b,
+            Operation::Add => write!(f, "+"),
+            Operation::Mult => write!(f, "*"),
+            Operation::Concat => write!(f, "||"),
         }
     }
 }
 
-fn recursive_op(
-    operations: &[Operation],
-    goal: u64,
-    cur_total: u64,
-    numbers: &[u64],
-) -> Result<Vec<(u64, Option<Operation>)>, ()> {
-    if let Some(num) = numbers.first() {
-        for op in operations {
-            let cur_total = op.compute(cur_total, *num);
-            let r = recursive_op(operations, goal, cur_total, &numbers[1..]);
-            if let Ok(mut r) = r {
-                r.push((*num, Some(*op)));
-                return Ok(r);
+struct Equation {
+    test_value: u64,
+    numbers: Vec<u64>,
+}
+
+impl Equation {
+    fn parse(line: &str) -> Result<Self, String> {
+        let (test_value, numbers) = line
+            .split_once(':')
+            .ok_or("expected `<test value>: <numbers>`")?;
+        let number = |n: &str| n.parse().map_err(|e| format!("invalid number `{n}`: {e}"));
+        let numbers: Vec<u64> = numbers.split_whitespace().map(number).try_collect()?;
+        if numbers.is_empty() {
+            return Err("expected at least one number".into());
+        }
+        Ok(Equation {
+            test_value: number(test_value)?,
+            numbers,
+        })
+    }
+
+    /// The operators that make the equation true, evaluated left to right.
+    ///
+    /// Works from the last number back to the first, undoing one operator at a time and pruning
+    /// every branch whose inverse doesn't exist.
+    fn solve(&self, operations: &[Operation]) -> Option<Vec<Operation>> {
+        fn solve_rec(
+            operations: &[Operation],
+            goal: u64,
+            numbers: &[u64],
+        ) -> Option<Vec<Operation>> {
+            match numbers {
+                [] => None,
+                [first] => (*first == goal).then(Vec::new),
+                [rest @ .., last] => operations.iter().find_map(|op| {
+                    let goal = op.undo(goal, *last)?;
+                    let mut solution = solve_rec(operations, goal, rest)?;
+                    solution.push(*op);
+                    Some(solution)
+                }),
             }
         }
-    } else if goal == cur_total {
-        return Ok(Vec::new());
+        solve_rec(operations, self.test_value, &self.numbers)
+    }
+
+    /// The left-to-right value of the numbers combined with `operations`.
+    fn evaluate(&self, operations: &[Operation]) -> Option<u64> {
+        let (first, rest) = self.numbers.split_first()?;
+        rest.iter()
+            .zip(operations)
+            .try_fold(*first, |total, (n, op)| op.compute(total, *n))
+    }
+
+    fn format(&self, operations: &[Operation]) -> String {
+        let mut s = format!("{} = {}", self.test_value, self.numbers[0]);
+        for (n, op) in self.numbers[1..].iter().zip(operations) {
+            s += &format!(" {op} {n}");
+        }
+        s
     }
-    Err(())
+}
+
+/// # Panics
+/// On the first line that isn't a valid equation.
+fn parse(input: &str) -> Vec<Equation> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            Equation::parse(line).unwrap_or_else(|e| panic!("line {}: {e}: `{line}`", i + 1))
+        })
+        .collect()
 }
 
 fn solve(operations: &[Operation], input: &str) -> Option<u64> {
-    let lines = input.lines().collect_vec();
-    let lines = lines
+    let total = parse(input)
         .iter()
-        .flat_map(|line| {
-            line.split_once(":").map(|(first, rest)| {
-                let first: u64 = first.parse().unwrap();
-                let rest: Vec<u64> = rest
-                    .split_whitespace()
-                    .map(|n| n.parse().unwrap())
-                    .collect();
-                (first, rest)
-            })
-        })
-        .flat_map(|(solution, numbers)| {
-            let r = recursive_op(
-                operations,
-                solution,
-                *numbers.first().unwrap(),
-                &numbers[1..],
-            );
-            //dbg!(solution, &r);
-            r.map(|_| solution)
-        })
+        .filter(|equation| equation.solve(operations).is_some())
+        .map(|equation| equation.test_value)
         .sum();
-
-    Some(lines)
+    Some(total)
 }
 
 pub fn part_one(input: &str) -> Option<u64> {
-    let operations = vec![Operation::Mult, Operation::Add];
-    solve(&operations, input)
+    solve(&PART_ONE_OPERATIONS, input)
 }
 
 pub fn part_two(input: &str) -> Option<u64> {
-    let operations = vec![Operation::Mult, Operation::Add, Operation::Concat];
-    solve(&operations, input)
+    solve(&PART_TWO_OPERATIONS, input)
+}
+
+/// Prints the operators found for every equation that can be made true.
+fn explain(input: &str) {
+    for equation in parse(input) {
+        if let Some(operations) = equation.solve(&PART_TWO_OPERATIONS) {
+            debug_assert_eq!(equation.evaluate(&operations), Some(equation.test_value));
+            println!("{}", equation.format(&operations));
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_compute_overflow() {
+        assert_eq!(Operation::Add.compute(u64::MAX, 1), None);
+        assert_eq!(Operation::Mult.compute(u64::MAX / 2, 3), None);
+        assert_eq!(Operation::Concat.compute(u64::MAX / 10, 10), None);
+        assert_eq!(Operation::Concat.compute(12, 345), Some(12345));
+        assert_eq!(Operation::Concat.compute(12, 0), Some(120));
+    }
+
+    #[test]
+    fn test_undo() {
+        for op in PART_TWO_OPERATIONS {
+            for (a, b) in [(12, 345), (7, 7), (100, 10), (0, 5)] {
+                let result = op.compute(a, b).unwrap();
+                assert_eq!(op.undo(result, b), Some(a), "{a} {op} {b}");
+            }
+        }
+        assert_eq!(Operation::Mult.undo(10, 3), None);
+        assert_eq!(Operation::Add.undo(2, 3), None);
+        assert_eq!(Operation::Concat.undo(1234, 35), None);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(Equation::parse("190: 10 19").unwrap().numbers, vec![10, 19]);
+        for line in ["190 10 19", "190: 10 x19", "-1: 10", "190:"] {
+            assert!(Equation::parse(line).is_err(), "{line}");
+        }
+        let panicked = std::panic::catch_unwind(|| parse("190: 10 19\n3267 81 40 27"));
+        assert!(panicked.is_err());
+    }
+
+    #[test]
+    fn test_explain() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let explained = parse(&input)
+            .iter()
+            .filter_map(|equation| {
+                let operations = equation.solve(&PART_TWO_OPERATIONS)?;
+                assert_eq!(equation.evaluate(&operations), Some(equation.test_value));
+                Some(equation.format(&operations))
+            })
+            .collect_vec();
+        assert_eq!(
+            explained,
+            vec![
+                "190 = 10 * 19",
+                "3267 = 81 * 40 + 27",
+                "156 = 15 || 6",
+                "7290 = 6 * 8 || 6 * 15",
+                "192 = 17 || 8 + 14",
+                "292 = 11 + 6 * 16 + 20",
+            ]
+        );
+    }
+
     #[test]
     fn test_7_a() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
@@ -1,58 +1,119 @@
+use advent_of_code::dag::PathCounter;
 use glam::IVec2;
-use itertools::Itertools;
-use nom::Parser;
-use pathfinding::prelude::strongly_connected_components_from;
+use pathfinding::prelude::bfs_reach;
 
 advent_of_code::solution!(10);
 
+/// Score and rating of a single trailhead.
+#[derive(Debug, PartialEq)]
+struct Trailhead {
+    position: IVec2,
+    /// The number of summits reachable from the trailhead.
+    score: usize,
+    /// The number of distinct trails from the trailhead to any summit.
+    rating: u64,
+}
+
+struct TopoMap {
+    map_size: IVec2,
+    heights: Vec<u8>,
+}
+
+impl TopoMap {
+    fn parse(input: &str) -> Self {
+        let width = input.split_whitespace().next().unwrap().len();
+        let height = input.split_whitespace().count();
+        let heights = input
+            .split_whitespace()
+            .flat_map(|s| s.bytes())
+            // impassable tiles in some of the examples are marked with `.`.
+            .map(|b| {
+                if b.is_ascii_digit() {
+                    b - b'0'
+                } else {
+                    u8::MAX
+                }
+            })
+            .collect();
+        TopoMap {
+            map_size: IVec2::new(width as i32, height as i32),
+            heights,
+        }
+    }
+
+    fn height(&self, c: &IVec2) -> u8 {
+        self.heights[c.y as usize * self.map_size.x as usize + c.x as usize]
+    }
+
+    fn positions(&self) -> impl Iterator<Item = IVec2> + '_ {
+        (0..self.heights.len() as i32).map(|i| IVec2::new(i % self.map_size.x, i / self.map_size.x))
+    }
+
+    /// Neighbours which are exactly one step higher.
+    fn successors(&self, c: &IVec2) -> Vec<IVec2> {
+        let next_height = self.height(c) + 1;
+        [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y]
+            .into_iter()
+            .map(|d| c + d)
+            .filter(|n| n.cmpge(IVec2::ZERO).all() && n.cmplt(self.map_size).all())
+            .filter(|n| self.height(n) == next_height)
+            .collect()
+    }
+
+    fn is_summit(&self, c: &IVec2) -> bool {
+        self.height(c) == 9
+    }
+
+    /// Scores and rates every trailhead, in reading order.
+    fn trailheads(&self) -> Vec<Trailhead> {
+        let mut trails = PathCounter::new(|c| self.successors(c), |c| self.is_summit(c));
+        self.positions()
+            .filter(|c| self.height(c) == 0)
+            .map(|position| Trailhead {
+                position,
+                score: bfs_reach(position, |c| self.successors(c))
+                    .filter(|c| self.is_summit(c))
+                    .count(),
+                rating: trails.count(&position),
+            })
+            .collect()
+    }
+}
+
 pub fn part_one(input: &str) -> Option<u32> {
-    let width = input.split_whitespace().next().unwrap().len();
-    let height = input.split_whitespace().count();
-    let from_index = |i: usize| -> IVec2 {
-        let x = i % width;
-        let y = i / width;
-        IVec2::new(x as i32, y as i32)
-    };
-    let from_vec2 = |c: &IVec2| -> usize { c.y as usize * width + c.x as usize };
-    let input: Vec<char> = input.split_whitespace().flat_map(|s| s.chars()).collect();
-    let map_size = IVec2::new(width as i32, height as i32);
-
-    let ans: usize = input
-        .iter()
-        .enumerate()
-        .filter_map(|(i, c)| (c == &'0').then_some(from_index(i)))
-        .map(|zero| {
-            let nodes = strongly_connected_components_from(&zero, |c| {
-                let num = input[from_vec2(c)].to_string().parse::<u32>().unwrap();
-                let next_num = num + 1;
-                [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y]
-                    .into_iter()
-                    .filter_map(|d| {
-                        let new = c + d;
-                        (new.cmpge(IVec2::ZERO).all() && new.cmplt(map_size).all()).then_some(new)
-                    })
-                    .filter(|d| input[from_vec2(&d)].to_string() == next_num.to_string())
-                    .collect_vec()
-            });
-            nodes
-                .into_iter()
-                .flatten()
-                .filter(|n| input[from_vec2(n)] == '9')
-                .count()
-        })
-        .inspect(|score| println!("score: {}", score))
-        .sum();
-    Some(ans as u32)
+    let map = TopoMap::parse(input);
+    Some(map.trailheads().iter().map(|t| t.score as u32).sum())
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    None
+pub fn part_two(input: &str) -> Option<u64> {
+    let map = TopoMap::parse(input);
+    Some(map.trailheads().iter().map(|t| t.rating).sum())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_trailheads() {
+        let map = TopoMap::parse(&advent_of_code::template::read_file("examples", DAY));
+        let trailheads = map.trailheads();
+        assert_eq!(
+            trailheads.iter().map(|t| t.score).collect::<Vec<_>>(),
+            vec![5, 6, 5, 3, 1, 3, 5, 3, 5]
+        );
+        assert_eq!(
+            trailheads.iter().map(|t| t.rating).collect::<Vec<_>>(),
+            vec![20, 24, 10, 4, 1, 4, 5, 8, 5]
+        );
+    }
+
+    #[test]
+    fn test_rating_with_impassable_tiles() {
+        let input = ".....0.\n..4321.\n..5..2.\n..6543.\n..7..4.\n..8765.\n..9....\n";
+        assert_eq!(part_two(input), Some(3));
+    }
+
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
@@ -62,6 +123,6 @@ mod tests {
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result, Some(81));
     }
 }
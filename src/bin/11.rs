@@ -2,47 +2,89 @@ use nom::character::complete;
 use nom::character::complete::space1;
 use nom::multi::separated_list1;
 use nom::IResult;
+use std::collections::HashMap;
 
 advent_of_code::solution!(11);
 
 type Stone = u64;
 
-fn blink(stone: Stone) -> Vec<Stone> {
+/// Splits a number with an even number of digits into its left and right half.
+fn split_digits(num: Stone) -> Option<(Stone, Stone)> {
+    let digits = num.ilog10() + 1;
+    digits.is_multiple_of(2).then(|| {
+        let half = 10u64.pow(digits / 2);
+        (num / half, num % half)
+    })
+}
+
+/// Applies the first matching rule to a stone, which may split it in two.
+fn blink(stone: Stone) -> (Stone, Option<Stone>) {
     match stone {
-        0 => vec![1],
-        num if num.to_string().len() % 2 == 0 => {
-            let num = num.to_string();
-            let mid = num.len() / 2;
-            let (left, right) = num.split_at(mid);
-            let left = left.parse().unwrap();
-            let right = right.parse().unwrap();
-            vec![left, right]
+        0 => (1, None),
+        num => match split_digits(num) {
+            Some((left, right)) => (left, Some(right)),
+            None => (num * 2024, None),
+        },
+    }
+}
+
+/// Blinks `n` times and returns how many stones of each number there are.
+///
+/// Stones never affect each other, so stones with the same number are evolved once and tracked
+/// by their count.
+pub fn blink_n(stones: &[Stone], n: usize) -> HashMap<Stone, u64> {
+    let mut counts: HashMap<Stone, u64> = HashMap::new();
+    for stone in stones {
+        *counts.entry(*stone).or_default() += 1;
+    }
+    for _ in 0..n {
+        let mut next = HashMap::with_capacity(counts.len());
+        for (stone, count) in counts {
+            let (left, right) = blink(stone);
+            *next.entry(left).or_default() += count;
+            if let Some(right) = right {
+                *next.entry(right).or_default() += count;
+            }
         }
-        num => vec![num * 2024],
+        counts = next;
     }
+    counts
 }
 
 fn parse(input: &str) -> IResult<&str, Vec<Stone>> {
     separated_list1(space1, complete::u64)(input)
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let (_, mut stones) = parse(input).unwrap();
-    for i in 0..75 {
-        println!("working on {i}");
-        stones = stones.into_iter().flat_map(|stone| blink(stone)).collect();
-    }
-    Some(stones.len() as u32)
+pub fn part_one(input: &str) -> Option<u64> {
+    let (_, stones) = parse(input).unwrap();
+    Some(blink_n(&stones, 25).values().sum())
 }
 
-pub fn part_two(input: &str) -> Option<Stone> {
-    None
+pub fn part_two(input: &str) -> Option<u64> {
+    let (_, stones) = parse(input).unwrap();
+    Some(blink_n(&stones, 75).values().sum())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_blink() {
+        assert_eq!(blink(0), (1, None));
+        assert_eq!(blink(1), (2024, None));
+        assert_eq!(blink(10), (1, Some(0)));
+        assert_eq!(blink(99), (9, Some(9)));
+        assert_eq!(blink(999), (2021976, None));
+        assert_eq!(blink(1000), (10, Some(0)));
+    }
+
+    #[test]
+    fn test_blink_n() {
+        assert_eq!(blink_n(&[0, 1, 10, 99, 999], 1).values().sum::<u64>(), 7);
+        assert_eq!(blink_n(&[125, 17], 6).values().sum::<u64>(), 22);
+    }
+
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
@@ -52,6 +94,6 @@ mod tests {
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result, Some(65601038650482));
     }
 }
@@ -1,21 +1,51 @@
+use advent_of_code::bitgrid::BitGrid;
+use advent_of_code::template::stress::Rng;
 use glam::IVec2;
 use itertools::Itertools;
-use pathfinding::prelude::*;
-use std::collections::HashSet;
+use pathfinding::prelude::{astar_bag, dijkstra};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashSet};
 
-advent_of_code::solution!(16);
+advent_of_code::solution!(16, modes: {
+    "--explain" => explain,
+}, generator: generate, variants: {
+    1 => part_one_dijkstra,
+    2 => part_two_astar_bag,
+});
 
 struct Map {
     map_size: IVec2,
-    walls: Vec<IVec2>,
+    walls: BitGrid,
     start: IVec2,
     end: IVec2,
 }
 
+/// A reindeer's position and the direction it is facing.
+type State = (IVec2, IVec2);
+
+const FACINGS: [IVec2; 4] = [IVec2::X, IVec2::Y, IVec2::NEG_X, IVec2::NEG_Y];
+
+/// A single leg of a path, merging consecutive steps forward.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Move {
+    Forward(usize),
+    TurnClockwise,
+    TurnCounterclockwise,
+}
+
+impl Move {
+    fn cost(&self) -> usize {
+        match self {
+            Move::Forward(steps) => *steps,
+            Move::TurnClockwise | Move::TurnCounterclockwise => 1000,
+        }
+    }
+}
+
 impl Map {
-    fn successors(&self, pos: &IVec2, facing: &IVec2) -> Vec<((IVec2, IVec2), usize)> {
+    fn successors(&self, pos: &IVec2, facing: &IVec2) -> Vec<(State, usize)> {
         let next_pos = pos + facing;
-        if self.walls.contains(&next_pos) {
+        if self.walls.contains(next_pos) {
             vec![
                 ((*pos, facing.perp()), 1000),
                 ((*pos, -facing.perp()), 1000),
@@ -28,11 +58,153 @@ impl Map {
             ]
         }
     }
-    #[allow(unused)]
+
+    /// The states from which a single move leads to `(pos, facing)`, with the cost of that move.
+    fn predecessors(&self, pos: &IVec2, facing: &IVec2) -> Vec<(State, usize)> {
+        let previous_pos = pos - facing;
+        if self.walls.contains(previous_pos) {
+            vec![
+                ((*pos, facing.perp()), 1000),
+                ((*pos, -facing.perp()), 1000),
+            ]
+        } else {
+            vec![
+                ((previous_pos, *facing), 1),
+                ((*pos, facing.perp()), 1000),
+                ((*pos, -facing.perp()), 1000),
+            ]
+        }
+    }
+
+    fn state_index(&self, (pos, facing): &State) -> usize {
+        let facing = FACINGS.iter().position(|f| f == facing).unwrap();
+        (pos.y * self.map_size.x + pos.x) as usize * FACINGS.len() + facing
+    }
+
+    fn end_states(&self) -> impl Iterator<Item = State> + '_ {
+        FACINGS.into_iter().map(|facing| (self.end, facing))
+    }
+
+    /// The lowest cost of reaching every state from any of `sources`, indexed by
+    /// [`Map::state_index`]. Unreachable states cost `usize::MAX`.
+    fn costs_from(
+        &self,
+        sources: impl IntoIterator<Item = State>,
+        edges: impl Fn(&IVec2, &IVec2) -> Vec<(State, usize)>,
+    ) -> Vec<usize> {
+        let mut costs = vec![usize::MAX; (self.map_size.x * self.map_size.y) as usize * 4];
+        let mut queue = BinaryHeap::new();
+        for source in sources {
+            costs[self.state_index(&source)] = 0;
+            queue.push(Reverse((0, source.0.to_array(), source.1.to_array())));
+        }
+        while let Some(Reverse((cost, pos, facing))) = queue.pop() {
+            let (pos, facing) = (IVec2::from_array(pos), IVec2::from_array(facing));
+            if cost > costs[self.state_index(&(pos, facing))] {
+                continue;
+            }
+            for (next, edge_cost) in edges(&pos, &facing) {
+                let next_cost = cost + edge_cost;
+                let known_cost = &mut costs[self.state_index(&next)];
+                if next_cost < *known_cost {
+                    *known_cost = next_cost;
+                    queue.push(Reverse((next_cost, next.0.to_array(), next.1.to_array())));
+                }
+            }
+        }
+        costs
+    }
+
+    /// The lowest cost of reaching every state from the start.
+    fn costs_from_start(&self) -> Vec<usize> {
+        self.costs_from([(self.start, IVec2::X)], |p, f| self.successors(p, f))
+    }
+
+    /// The lowest cost of reaching the end from every state.
+    fn costs_to_end(&self) -> Vec<usize> {
+        self.costs_from(self.end_states(), |p, f| self.predecessors(p, f))
+    }
+
+    fn lowest_score(&self, from_start: &[usize]) -> Option<usize> {
+        self.end_states()
+            .map(|state| from_start[self.state_index(&state)])
+            .min()
+            .filter(|cost| *cost != usize::MAX)
+    }
+
+    /// Every tile that is part of at least one best path.
+    ///
+    /// A state lies on a best path if the cost of reaching it from the start plus the cost of
+    /// reaching the end from it equals the lowest score.
+    fn best_path_tiles(&self) -> Vec<IVec2> {
+        let from_start = self.costs_from_start();
+        let to_end = self.costs_to_end();
+        let Some(lowest_score) = self.lowest_score(&from_start) else {
+            return vec![];
+        };
+        (0..self.map_size.y)
+            .flat_map(|y| (0..self.map_size.x).map(move |x| IVec2::new(x, y)))
+            .filter(|pos| {
+                FACINGS.iter().any(|facing| {
+                    let i = self.state_index(&(*pos, *facing));
+                    from_start[i].saturating_add(to_end[i]) == lowest_score
+                })
+            })
+            .collect()
+    }
+
+    /// One of the best paths, as the states it passes through.
+    fn best_path(&self) -> Option<Vec<State>> {
+        let from_start = self.costs_from_start();
+        let lowest_score = self.lowest_score(&from_start)?;
+        let mut state = self
+            .end_states()
+            .find(|state| from_start[self.state_index(state)] == lowest_score)?;
+        let mut path = vec![state];
+        while state != (self.start, IVec2::X) {
+            let cost = from_start[self.state_index(&state)];
+            state = self
+                .predecessors(&state.0, &state.1)
+                .into_iter()
+                .find(|(previous, edge_cost)| {
+                    from_start[self.state_index(previous)].saturating_add(*edge_cost) == cost
+                })?
+                .0;
+            path.push(state);
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// Breaks a path down into moves, each with its cost and the total cost after it.
+    fn moves(path: &[State]) -> Vec<(Move, usize, usize)> {
+        let mut moves: Vec<Move> = vec![];
+        for ((pos, facing), (next_pos, next_facing)) in path.iter().tuple_windows() {
+            let movement = if pos != next_pos {
+                Move::Forward(1)
+            } else if facing.perp() == *next_facing {
+                Move::TurnClockwise
+            } else {
+                Move::TurnCounterclockwise
+            };
+            match (moves.last_mut(), movement) {
+                (Some(Move::Forward(steps)), Move::Forward(1)) => *steps += 1,
+                _ => moves.push(movement),
+            }
+        }
+        moves
+            .into_iter()
+            .scan(0, |total, movement| {
+                *total += movement.cost();
+                Some((movement, movement.cost(), *total))
+            })
+            .collect()
+    }
+
     fn print_path(&self, path: &[IVec2]) {
         for y in 0..self.map_size.y {
             for x in 0..self.map_size.x {
-                if self.walls.contains(&IVec2::new(x, y)) {
+                if self.walls.contains(IVec2::new(x, y)) {
                     print!("#");
                 } else if path.contains(&IVec2::new(x, y)) {
                     print!("0");
@@ -46,15 +218,17 @@ impl Map {
 }
 
 fn parse(input: &str) -> Map {
-    let walls = input
-        .split_whitespace()
-        .enumerate()
-        .flat_map(|(y, line)| {
+    let height = input.split_whitespace().count();
+    let width = input.split_whitespace().next().unwrap().len();
+    let map_size = IVec2::new(width as i32, height as i32);
+    let walls = BitGrid::from_positions(
+        map_size,
+        input.split_whitespace().enumerate().flat_map(|(y, line)| {
             line.char_indices()
                 .filter(|(_, c)| c == &'#')
                 .map(move |(x, _)| IVec2::new(x as i32, y as i32))
-        })
-        .collect_vec();
+        }),
+    );
     let start = input
         .split_whitespace()
         .enumerate()
@@ -75,48 +249,143 @@ fn parse(input: &str) -> Map {
         })
         .exactly_one()
         .unwrap();
-    let height = input.split_whitespace().count();
-    let width = input.split_whitespace().next().unwrap().len();
     Map {
-        map_size: IVec2::new(width as i32, height as i32),
+        map_size,
         walls,
         start,
         end,
     }
 }
 
+/// A maze of `size` by `size` rooms from the start in the bottom left to the end in the top
+/// right, with a few extra openings so there is more than one path.
+fn generate(seed: u64, size: usize) -> String {
+    let mut rng = Rng::new(seed);
+    let width = 2 * size.max(2) + 1;
+    let mut grid = vec![vec!['#'; width]; width];
+    let mut stack = vec![(1, 1)];
+    grid[1][1] = '.';
+    while let Some(&(x, y)) = stack.last() {
+        let unvisited = [(2, 0), (0, 2), (-2, 0), (0, -2)]
+            .iter()
+            .map(|(dx, dy)| (x as i32 + dx, y as i32 + dy))
+            .filter(|&(x, y)| x > 0 && y > 0 && x < width as i32 && y < width as i32)
+            .map(|(x, y)| (x as usize, y as usize))
+            .filter(|&(x, y)| grid[y][x] == '#')
+            .collect_vec();
+        if unvisited.is_empty() {
+            stack.pop();
+            continue;
+        }
+        let (nx, ny) = *rng.choose(&unvisited);
+        grid[(y + ny) / 2][(x + nx) / 2] = '.';
+        grid[ny][nx] = '.';
+        stack.push((nx, ny));
+    }
+    for _ in 0..size {
+        let (x, y) = (rng.range(1..width - 1), rng.range(1..width - 1));
+        grid[y][x] = '.';
+    }
+    grid[width - 2][1] = 'S';
+    grid[1][width - 2] = 'E';
+    grid.iter().map(|row| row.iter().join("") + "\n").collect()
+}
+
 pub fn part_one(input: &str) -> Option<u32> {
+    let map = parse(input);
+    let cost = map.lowest_score(&map.costs_from_start())?;
+    Some(cost as u32)
+}
+
+pub fn part_two(input: &str) -> Option<u32> {
+    let map = parse(input);
+    let tiles = map.best_path_tiles();
+    (!tiles.is_empty()).then_some(tiles.len() as u32)
+}
+
+pub fn part_one_dijkstra(input: &str) -> Option<u32> {
     let map = parse(input);
     let (_, cost) = dijkstra(
         &(map.start, IVec2::X),
         |(p, facing)| map.successors(p, facing),
         |(p, _)| p == &map.end,
-    )
-    .unwrap();
-
+    )?;
     Some(cost as u32)
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
+/// Enumerates every best path, which is only feasible if there are few of them.
+pub fn part_two_astar_bag(input: &str) -> Option<u32> {
     let map = parse(input);
     let (paths, _) = astar_bag(
         &(map.start, IVec2::X),
         |(p, facing)| map.successors(p, facing),
         |_| 0,
         |(p, _)| p == &map.end,
-    )
-    .unwrap();
+    )?;
     let tiles = paths
         .flat_map(|path| path.into_iter().map(|(p, _)| p))
         .collect::<HashSet<_>>();
-
     Some(tiles.len() as u32)
 }
 
+/// Prints one of the best paths with the cost of every move.
+fn explain(input: &str) {
+    let map = parse(input);
+    let Some(path) = map.best_path() else {
+        println!("The end can not be reached.");
+        return;
+    };
+    map.print_path(&path.iter().map(|(pos, _)| *pos).collect_vec());
+    println!();
+    for (movement, cost, total) in Map::moves(&path) {
+        let movement = match movement {
+            Move::Forward(steps) => format!("forward {steps}"),
+            Move::TurnClockwise => "turn clockwise".into(),
+            Move::TurnCounterclockwise => "turn counterclockwise".into(),
+        };
+        println!("{movement:<22} {:>6} {total:>8}", format!("+{cost}"));
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_best_path() {
+        let map = parse(&advent_of_code::template::read_file("examples", DAY));
+        let path = map.best_path().unwrap();
+        assert_eq!(path.first(), Some(&(map.start, IVec2::X)));
+        assert_eq!(path.last().map(|s| s.0), Some(map.end));
+
+        let moves = Map::moves(&path);
+        assert_eq!(moves.last().unwrap().2, 7036);
+        assert_eq!(
+            moves
+                .iter()
+                .filter(|m| matches!(m.0, Move::Forward(_)))
+                .map(|m| m.1)
+                .sum::<usize>(),
+            36
+        );
+        assert_eq!(moves[0], (Move::TurnCounterclockwise, 1000, 1000));
+    }
+
+    #[test]
+    fn test_second_example() {
+        let input = advent_of_code::template::read_file_part("examples", DAY, 2);
+        assert_eq!(part_one(&input), Some(11048));
+        assert_eq!(part_two(&input), Some(64));
+    }
+
+    #[test]
+    fn test_open_room() {
+        // any path with a second turn is worse, so only the path along the walls is best.
+        let input = "#######\n#....E#\n#.....#\n#.....#\n#S....#\n#######\n";
+        assert_eq!(part_one(input), Some(1007));
+        assert_eq!(part_two(input), Some(8));
+    }
+
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
@@ -8,9 +8,16 @@ use nom::sequence::{preceded, separated_pair, tuple};
 use nom::IResult;
 use std::collections::HashMap;
 use std::fmt::Debug;
+use std::hint::black_box;
 use std::ops::AddAssign;
+use std::time::Instant;
 
-advent_of_code::solution!(17);
+advent_of_code::solution!(17, modes: {
+    "--disassemble" => disassemble,
+    "--trace" => trace,
+    "--debug" => debug,
+    "--bench" => bench,
+});
 
 #[derive(Default, Clone)]
 struct Computer {
@@ -51,7 +58,6 @@ impl Computer {
         let opcode = OpCode::new(self.instructions[self.instruction_pointer]);
         let operand = self.instructions[self.instruction_pointer + 1];
         let mut increase = true;
-        // println!("running {opcode:?} with operand: {operand:?}");
         self.op_count.entry(opcode).or_default().add_assign(1);
         match opcode {
             Adv => self.dv(Register::A, operand),
@@ -61,11 +67,8 @@ impl Computer {
             Bst => self.set_register(Register::B, self.evaluate_combo(operand) % 8),
             Jnz => {
                 if self.a != 0 {
-                    // println!("jumping to {operand:?}");
                     self.instruction_pointer = operand.as_usize();
                     increase = false;
-                    // } else {
-                    //     println!("not jumping");
                 }
             }
             Bxc => {
@@ -100,7 +103,7 @@ impl Computer {
         }
     }
 
-    fn register(&mut self, register: Register) -> i64 {
+    fn register(&self, register: Register) -> i64 {
         match register {
             Register::A => self.a,
             Register::B => self.b,
@@ -109,7 +112,6 @@ impl Computer {
     }
 
     fn set_register(&mut self, register: Register, value: i64) {
-        // println!("setting register {register:?} to {value}");
         match register {
             Register::A => self.a = value,
             Register::B => self.b = value,
@@ -124,6 +126,174 @@ impl Computer {
         }
         steps
     }
+
+    fn is_halted(&self) -> bool {
+        self.instruction_pointer + 1 >= self.instructions.len()
+    }
+
+    fn registers(&self) -> Registers {
+        Registers {
+            a: self.a,
+            b: self.b,
+            c: self.c,
+        }
+    }
+
+    /// The instruction at the instruction pointer, or `None` once the program has halted.
+    fn current_instruction(&self) -> Option<Instruction> {
+        self.instructions
+            .get(self.instruction_pointer..self.instruction_pointer + 2)
+            .map(|i| Instruction::new(i[0], i[1]))
+    }
+
+    /// Executes a single instruction and records what it changed.
+    fn step_traced(&mut self) -> Option<TraceEntry> {
+        let instruction = self.current_instruction()?;
+        let instruction_pointer = self.instruction_pointer;
+        let before = self.registers();
+        let output_len = self.output.len();
+        self.step();
+        Some(TraceEntry {
+            instruction_pointer,
+            instruction,
+            before,
+            after: self.registers(),
+            output: self.output.get(output_len).copied(),
+        })
+    }
+
+    /// Runs the program to completion, returning a trace entry for every executed instruction.
+    fn trace(&mut self) -> Vec<TraceEntry> {
+        std::iter::from_fn(|| self.step_traced()).collect()
+    }
+
+    /// Renders the program as one line of pseudo-code per instruction.
+    fn disassemble(&self) -> Vec<String> {
+        self.instructions
+            .iter()
+            .tuples()
+            .enumerate()
+            .map(|(i, (opcode, operand))| {
+                let instruction = Instruction::new(*opcode, *operand);
+                format!(
+                    "{:>3}: {instruction:<6} {}",
+                    i * 2,
+                    instruction.pseudo_code()
+                )
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Registers {
+    a: i64,
+    b: i64,
+    c: i64,
+}
+
+impl Registers {
+    fn combo(&self, combo: Combo) -> i64 {
+        match combo {
+            Combo::Literal(n) => n,
+            Combo::A => self.a,
+            Combo::B => self.b,
+            Combo::C => self.c,
+            Combo::Reserved => panic!("operand 7 is reserved"),
+        }
+    }
+
+    fn get(&self, register: Register) -> i64 {
+        match register {
+            Register::A => self.a,
+            Register::B => self.b,
+            Register::C => self.c,
+        }
+    }
+}
+
+/// A decoded opcode and its operand.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Instruction {
+    opcode: OpCode,
+    operand: U3,
+}
+
+impl Instruction {
+    fn new(opcode: U3, operand: U3) -> Self {
+        Self {
+            opcode: OpCode::new(opcode),
+            operand,
+        }
+    }
+
+    /// Renders the combo operand as the literal or register it refers to.
+    fn combo(&self) -> String {
+        match self.operand.0 {
+            0..=3 => self.operand.to_string(),
+            4 => "A".into(),
+            5 => "B".into(),
+            6 => "C".into(),
+            _ => "<reserved>".into(),
+        }
+    }
+
+    fn pseudo_code(&self) -> String {
+        use OpCode::*;
+        match self.opcode {
+            Adv => format!("A = A >> {}", self.combo()),
+            Bdv => format!("B = A >> {}", self.combo()),
+            Cdv => format!("C = A >> {}", self.combo()),
+            Bxl => format!("B = B ^ {}", self.operand),
+            Bst => format!("B = {} % 8", self.combo()),
+            Jnz => format!("if A != 0 jump {}", self.operand),
+            Bxc => "B = B ^ C".into(),
+            Out => format!("out {} % 8", self.combo()),
+        }
+    }
+}
+
+impl std::fmt::Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mnemonic = format!("{:?}", self.opcode).to_lowercase();
+        f.pad(&format!("{mnemonic} {}", self.operand))
+    }
+}
+
+/// The effect of executing a single instruction.
+struct TraceEntry {
+    instruction_pointer: usize,
+    instruction: Instruction,
+    before: Registers,
+    after: Registers,
+    output: Option<U3>,
+}
+
+impl TraceEntry {
+    fn changed(&self, register: Register) -> bool {
+        self.before.get(register) != self.after.get(register)
+    }
+}
+
+impl std::fmt::Display for TraceEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut effects = [Register::A, Register::B, Register::C]
+            .into_iter()
+            .filter(|r| self.changed(*r))
+            .map(|r| format!("{r:?}: {} -> {}", self.before.get(r), self.after.get(r)))
+            .collect_vec();
+        if let Some(output) = self.output {
+            effects.push(format!("out: {output}"));
+        }
+        let line = format!(
+            "{:>3}: {:<6} {:<18} {}",
+            self.instruction_pointer,
+            self.instruction,
+            self.instruction.pseudo_code(),
+            effects.iter().map(|e| format!("| {e}")).join(" ")
+        );
+        f.write_str(line.trim_end())
+    }
 }
 
 #[derive(Debug, PartialEq, Copy, Clone, Eq, Hash)]
@@ -183,6 +353,114 @@ impl U3 {
     }
 }
 
+/// A combo operand, resolved when the program is compiled.
+#[derive(Debug, Clone, Copy)]
+enum Combo {
+    Literal(i64),
+    A,
+    B,
+    C,
+    Reserved,
+}
+
+/// An instruction with its opcode and operand decoded.
+#[derive(Debug, Clone, Copy)]
+enum Op {
+    Adv(Combo),
+    Bdv(Combo),
+    Cdv(Combo),
+    Bxl(i64),
+    Bst(Combo),
+    Jnz(usize),
+    Bxc,
+    Out(Combo),
+}
+
+impl Op {
+    fn decode(opcode: U3, operand: U3) -> Self {
+        use OpCode::*;
+        let combo = match operand.0 {
+            0..=3 => Combo::Literal(operand.as_i64()),
+            4 => Combo::A,
+            5 => Combo::B,
+            6 => Combo::C,
+            _ => Combo::Reserved,
+        };
+        match OpCode::new(opcode) {
+            Adv => Op::Adv(combo),
+            Bdv => Op::Bdv(combo),
+            Cdv => Op::Cdv(combo),
+            Bxl => Op::Bxl(operand.as_i64()),
+            Bst => Op::Bst(combo),
+            Jnz => Op::Jnz(operand.as_usize()),
+            Bxc => Op::Bxc,
+            Out => Op::Out(combo),
+        }
+    }
+}
+
+/// A program decoded ahead of time, so that it can be run many times without decoding
+/// instructions or allocating.
+///
+/// An instruction is decoded at every address, including odd ones, so jumps behave exactly like
+/// they do on the [`Computer`].
+struct Program {
+    ops: Vec<Op>,
+}
+
+impl Program {
+    fn compile(instructions: &[U3]) -> Self {
+        Self {
+            ops: instructions
+                .iter()
+                .tuple_windows()
+                .map(|(opcode, operand)| Op::decode(*opcode, *operand))
+                .collect(),
+        }
+    }
+
+    /// Runs the program until it halts or `out` returns `false`, passing every output to `out`.
+    ///
+    /// Shifts stand in for the division by a power of two, which is equivalent as long as the
+    /// registers are not negative.
+    fn run(&self, mut r: Registers, mut out: impl FnMut(U3) -> bool) -> Registers {
+        let mut ip = 0;
+        while let Some(op) = self.ops.get(ip) {
+            ip += 2;
+            match *op {
+                Op::Adv(combo) => r.a >>= r.combo(combo).min(63),
+                Op::Bdv(combo) => r.b = r.a >> r.combo(combo).min(63),
+                Op::Cdv(combo) => r.c = r.a >> r.combo(combo).min(63),
+                Op::Bxl(literal) => r.b ^= literal,
+                Op::Bst(combo) => r.b = r.combo(combo) % 8,
+                Op::Jnz(target) => {
+                    if r.a != 0 {
+                        ip = target;
+                    }
+                }
+                Op::Bxc => r.b ^= r.c,
+                Op::Out(combo) => {
+                    if !out(U3((r.combo(combo) % 8) as u8)) {
+                        break;
+                    }
+                }
+            }
+        }
+        r
+    }
+
+    /// Whether running the program with the given registers outputs exactly `expected`.
+    fn outputs(&self, registers: Registers, expected: &[U3]) -> bool {
+        let mut expected = expected.iter();
+        let mut matches = true;
+        self.run(registers, |output| {
+            matches = expected.next() == Some(&output);
+            matches
+        });
+        matches && expected.next().is_none()
+    }
+}
+
 fn parse_register(input: &str) -> IResult<&str, (Register, i64)> {
     let (input, (_, name, _, value)) = tuple((
         tag("Register "),
@@ -228,36 +506,352 @@ fn parse(input: &str) -> IResult<&str, Computer> {
 }
 
 pub fn part_one(input: &str) -> Option<String> {
-    let mut steps = 0;
     let (_, mut computer) = parse(input).unwrap();
-    while computer.instruction_pointer < computer.instructions.len() {
-        computer.step();
-        steps += 1;
+    computer.run();
+    Some(computer.output.into_iter().join(","))
+}
+
+/// Reasons the reverse solver can fail to find a quine.
+#[derive(Debug, PartialEq)]
+enum QuineError {
+    /// The program does not shift A by 3 bits, output once and jump back to the start.
+    UnsupportedProgram(&'static str),
+    /// No value of register A makes the program output itself.
+    NoSolution,
+}
+
+impl std::fmt::Display for QuineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QuineError::UnsupportedProgram(reason) => write!(f, "unsupported program: {reason}"),
+            QuineError::NoSolution => write!(f, "no value of register A outputs the program"),
+        }
     }
-    println!("steps: {steps}");
+}
 
-    println!("opcodes {:#?}", computer.op_count);
-    Some(computer.output.into_iter().join(","))
+impl Computer {
+    /// Runs a copy of the computer with register A set to `a` and returns its output.
+    fn output_for(&self, a: i64) -> Vec<U3> {
+        let mut c = self.clone();
+        c.a = a;
+        c.run();
+        c.output
+    }
+
+    /// Checks that the program is a single loop which consumes 3 bits of A per iteration.
+    fn check_quine_shape(&self) -> Result<(), QuineError> {
+        let instructions = self
+            .instructions
+            .iter()
+            .tuples()
+            .map(|(opcode, operand)| (OpCode::new(*opcode), operand.0))
+            .collect_vec();
+        if instructions.last() != Some(&(OpCode::Jnz, 0)) {
+            return Err(QuineError::UnsupportedProgram("must end with `jnz 0`"));
+        }
+        if instructions.iter().filter(|i| i.0 == OpCode::Jnz).count() != 1 {
+            return Err(QuineError::UnsupportedProgram("must contain a single jump"));
+        }
+        if instructions.iter().filter(|i| i.0 == OpCode::Out).count() != 1 {
+            return Err(QuineError::UnsupportedProgram("must output once per loop"));
+        }
+        if !instructions.contains(&(OpCode::Adv, 3))
+            || instructions.iter().filter(|i| i.0 == OpCode::Adv).count() != 1
+        {
+            return Err(QuineError::UnsupportedProgram(
+                "must shift A by `adv 3` once per loop",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Finds the lowest value of register A for which the program outputs itself.
+    ///
+    /// Each loop outputs one digit and drops the lowest 3 bits of A, so the last digit only
+    /// depends on the highest 3 bits. A is built from the last digit backwards, appending 3 bits
+    /// at a time and only descending into candidates which reproduce the tail of the program.
+    /// Candidates are tried in ascending order, so the first complete match is the minimum.
+    fn find_quine(&self) -> Result<i64, QuineError> {
+        self.check_quine_shape()?;
+        let program = Program::compile(&self.instructions);
+        self.find_quine_from(&program, 0, self.instructions.len())
+            .ok_or(QuineError::NoSolution)
+    }
+
+    fn find_quine_from(&self, program: &Program, a: i64, matched_from: usize) -> Option<i64> {
+        if matched_from == 0 {
+            return Some(a);
+        }
+        let tail = &self.instructions[matched_from - 1..];
+        (0..8).find_map(|bits| {
+            let candidate = (a << 3) | bits;
+            let registers = Registers {
+                a: candidate,
+                ..self.registers()
+            };
+            program
+                .outputs(registers, tail)
+                .then(|| self.find_quine_from(program, candidate, matched_from - 1))
+                .flatten()
+        })
+    }
 }
 
 pub fn part_two(input: &str) -> Option<i64> {
+    let (_, computer) = parse(input).unwrap();
+    match computer.find_quine() {
+        Ok(a) => Some(a),
+        Err(e) => {
+            eprintln!("{e}");
+            None
+        }
+    }
+}
+
+fn disassemble(input: &str) {
+    let (_, computer) = parse(input).unwrap();
+    computer
+        .disassemble()
+        .iter()
+        .for_each(|line| println!("{line}"));
+}
+
+fn trace(input: &str) {
     let (_, mut computer) = parse(input).unwrap();
+    computer
+        .trace()
+        .iter()
+        .for_each(|entry| println!("{entry}"));
+    println!("output: {}", computer.output.iter().join(","));
+}
 
-    computer.a = 0;
-    let goal = computer.instructions.clone();
-    let a = loop {
-        let mut c = computer.clone();
-        dbg!(&c.a);
-        c.run();
-        println!("{}", c.output.clone().into_iter().join(","));
-        if c.output == goal {
-            break computer.a;
-        } else {
-            computer.a += 1;
-            dbg!(computer.a);
+fn debug(input: &str) {
+    let (_, computer) = parse(input).unwrap();
+    debugger::Debugger::new(computer)
+        .run(std::io::stdin().lock(), std::io::stdout())
+        .unwrap();
+}
+
+/// Compares running every value of register A up to `RUNS` on the [`Computer`] and as a
+/// compiled [`Program`].
+fn bench(input: &str) {
+    const RUNS: i64 = 100_000;
+    let (_, computer) = parse(input).unwrap();
+
+    let timer = Instant::now();
+    let interpreted = (0..RUNS)
+        .map(|a| black_box(computer.output_for(a)).len())
+        .sum::<usize>();
+    let interpreted_time = timer.elapsed();
+
+    let timer = Instant::now();
+    let program = Program::compile(&computer.instructions);
+    let compiled = (0..RUNS)
+        .map(|a| {
+            let registers = Registers {
+                a,
+                ..computer.registers()
+            };
+            let mut outputs = 0;
+            program.run(black_box(registers), |_| {
+                outputs += 1;
+                true
+            });
+            outputs
+        })
+        .sum::<usize>();
+    let compiled_time = timer.elapsed();
+
+    assert_eq!(interpreted, compiled, "outputs differ");
+    println!("Computer::run: {interpreted_time:.1?} ({RUNS} runs)");
+    println!("Program::run:  {compiled_time:.1?} ({RUNS} runs)");
+}
+
+/// An interactive debugger for the computer, started with `cargo solve 17 --debug`.
+mod debugger {
+    use super::{Computer, Register};
+    use itertools::Itertools;
+    use std::collections::BTreeSet;
+    use std::io::{self, BufRead, Write};
+
+    const HELP: &str = "\
+commands:
+  s, step [n]         execute the next n instructions (default 1)
+  c, continue         run until a breakpoint, a watched register changes or the program halts
+  b, break <ip>       toggle a breakpoint on an instruction pointer
+  w, watch <reg>      toggle a watch on register a, b or c
+  set <reg> <value>   set a register
+  r, regs             print the registers, instruction pointer and output
+  l, list             disassemble the program
+  stats               print how often each opcode was executed
+  reset               restore the initial state, keeping breakpoints and watches
+  h, help             print this message
+  q, quit             exit the debugger";
+
+    pub struct Debugger {
+        initial: Computer,
+        computer: Computer,
+        breakpoints: BTreeSet<usize>,
+        watches: Vec<Register>,
+    }
+
+    impl Debugger {
+        pub fn new(computer: Computer) -> Self {
+            Self {
+                initial: computer.clone(),
+                computer,
+                breakpoints: BTreeSet::new(),
+                watches: Vec::new(),
+            }
         }
-    };
-    Some(a)
+
+        /// Reads commands from `input` until it is exhausted or `quit` is entered.
+        pub fn run(&mut self, input: impl BufRead, mut out: impl Write) -> io::Result<()> {
+            writeln!(out, "{HELP}")?;
+            write!(out, "(dbg) ")?;
+            out.flush()?;
+            for line in input.lines() {
+                if !self.execute(line?.trim(), &mut out)? {
+                    break;
+                }
+                write!(out, "(dbg) ")?;
+                out.flush()?;
+            }
+            Ok(())
+        }
+
+        /// Executes a single command, returning `false` once the debugger should exit.
+        pub fn execute(&mut self, command: &str, out: &mut impl Write) -> io::Result<bool> {
+            let words = command.split_whitespace().collect_vec();
+            match words.as_slice() {
+                [] => {}
+                ["s" | "step"] => self.step(1, out)?,
+                ["s" | "step", n] => match n.parse() {
+                    Ok(n) => self.step(n, out)?,
+                    Err(_) => writeln!(out, "invalid step count `{n}`")?,
+                },
+                ["c" | "continue"] => self.resume(out)?,
+                ["b" | "break", ip] => match ip.parse() {
+                    Ok(ip) => {
+                        if self.breakpoints.remove(&ip) {
+                            writeln!(out, "removed breakpoint at {ip}")?;
+                        } else {
+                            self.breakpoints.insert(ip);
+                            writeln!(out, "added breakpoint at {ip}")?;
+                        }
+                    }
+                    Err(_) => writeln!(out, "invalid instruction pointer `{ip}`")?,
+                },
+                ["w" | "watch", register] => match register_from_str(register) {
+                    Some(register) => {
+                        if let Some(i) = self.watches.iter().position(|r| *r == register) {
+                            self.watches.remove(i);
+                            writeln!(out, "removed watch on {register:?}")?;
+                        } else {
+                            self.watches.push(register);
+                            writeln!(out, "added watch on {register:?}")?;
+                        }
+                    }
+                    None => writeln!(out, "invalid register `{register}`")?,
+                },
+                ["set", register, value] => match (register_from_str(register), value.parse()) {
+                    (Some(register), Ok(value)) => {
+                        self.computer.set_register(register, value);
+                        self.print_registers(out)?;
+                    }
+                    _ => writeln!(out, "usage: set <a|b|c> <value>")?,
+                },
+                ["r" | "regs"] => self.print_registers(out)?,
+                ["l" | "list"] => {
+                    for (i, line) in self.computer.disassemble().iter().enumerate() {
+                        let ip = i * 2;
+                        let cursor = if ip == self.computer.instruction_pointer {
+                            ">"
+                        } else {
+                            " "
+                        };
+                        let breakpoint = if self.breakpoints.contains(&ip) {
+                            "*"
+                        } else {
+                            " "
+                        };
+                        writeln!(out, "{cursor}{breakpoint}{line}")?;
+                    }
+                }
+                ["stats"] => {
+                    for (opcode, count) in self.computer.op_count.iter().sorted_by_key(|o| o.1) {
+                        writeln!(out, "{opcode:?}: {count}")?;
+                    }
+                }
+                ["reset"] => {
+                    self.computer = self.initial.clone();
+                    self.print_registers(out)?;
+                }
+                ["h" | "help"] => writeln!(out, "{HELP}")?,
+                ["q" | "quit"] => return Ok(false),
+                _ => writeln!(out, "unknown command `{command}`, type `help` for a list")?,
+            }
+            Ok(true)
+        }
+
+        fn step(&mut self, n: usize, out: &mut impl Write) -> io::Result<()> {
+            for _ in 0..n {
+                match self.computer.step_traced() {
+                    Some(entry) => writeln!(out, "{entry}")?,
+                    None => break,
+                }
+            }
+            self.print_halted(out)
+        }
+
+        fn resume(&mut self, out: &mut impl Write) -> io::Result<()> {
+            while let Some(entry) = self.computer.step_traced() {
+                if self.watches.iter().any(|r| entry.changed(*r)) {
+                    writeln!(out, "{entry}")?;
+                    writeln!(out, "watched register changed")?;
+                    return Ok(());
+                }
+                if self
+                    .breakpoints
+                    .contains(&self.computer.instruction_pointer)
+                {
+                    writeln!(out, "breakpoint at {}", self.computer.instruction_pointer)?;
+                    return self.print_registers(out);
+                }
+            }
+            self.print_halted(out)
+        }
+
+        fn print_halted(&self, out: &mut impl Write) -> io::Result<()> {
+            if self.computer.is_halted() {
+                writeln!(out, "program halted")?;
+                self.print_registers(out)?;
+            }
+            Ok(())
+        }
+
+        fn print_registers(&self, out: &mut impl Write) -> io::Result<()> {
+            let c = &self.computer;
+            writeln!(
+                out,
+                "ip: {} | A: {} | B: {} | C: {} | output: {}",
+                c.instruction_pointer,
+                c.a,
+                c.b,
+                c.c,
+                c.output.iter().join(",")
+            )
+        }
+    }
+
+    fn register_from_str(s: &str) -> Option<Register> {
+        match s {
+            "a" | "A" => Some(Register::A),
+            "b" | "B" => Some(Register::B),
+            "c" | "C" => Some(Register::C),
+            _ => None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -331,6 +925,96 @@ mod tests {
         }
         assert_eq!(c.b, 44354);
     }
+    #[test]
+    fn test_disassemble() {
+        let (_, computer) = parse(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+        assert_eq!(
+            computer.disassemble(),
+            vec![
+                "  0: adv 1  A = A >> 1",
+                "  2: out 4  out A % 8",
+                "  4: jnz 0  if A != 0 jump 0",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_trace() {
+        let mut c = Computer {
+            a: 10,
+            instructions: vec![5, 0, 5, 1, 5, 4]
+                .into_iter()
+                .map(U3::new)
+                .collect_vec(),
+            ..Computer::default()
+        };
+        let trace = c.trace();
+        assert_eq!(trace.len(), 3);
+        assert_eq!(trace[2].output, Some(U3(2)));
+
+        let mut c = Computer {
+            b: 29,
+            instructions: vec![1, 7].into_iter().map(U3::new).collect_vec(),
+            ..Computer::default()
+        };
+        let trace = c.trace();
+        assert!(trace[0].changed(Register::B));
+        assert_eq!(
+            trace[0].to_string(),
+            "  0: bxl 7  B = B ^ 7          | B: 29 -> 26"
+        );
+    }
+
+    #[test]
+    fn test_debugger() {
+        let (_, computer) = parse(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+        let mut debugger = debugger::Debugger::new(computer);
+        let commands = "break 4\ncontinue\nwatch a\ncontinue\nwatch a\nstep 2\nquit\nstep\n";
+        let mut out = Vec::new();
+        debugger.run(commands.as_bytes(), &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+
+        assert!(out.contains("breakpoint at 4\nip: 4 | A: 364 | B: 0 | C: 0 | output: 4\n"));
+        assert!(out
+            .contains("  0: adv 1  A = A >> 1         | A: 364 -> 182\nwatched register changed"));
+        assert!(
+            out.contains("  2: out 4  out A % 8          | out: 6\n  4: jnz 0  if A != 0 jump 0\n")
+        );
+        assert_eq!(out.matches("(dbg) ").count(), 7);
+    }
+
+    #[test]
+    fn test_compiled_program() {
+        let programs = [
+            "0,1,5,4,3,0",
+            "0,3,5,4,3,0",
+            "5,0,5,1,5,4",
+            "0,1,5,4,3,0,2,6,1,7,4,0,5,5,5,6",
+            "2,4,1,1,7,5,4,6,0,3,1,4,5,5,3,0",
+        ];
+        for instructions in programs {
+            let instructions = instructions
+                .split(',')
+                .map(|n| U3::new(n.parse().unwrap()))
+                .collect_vec();
+            let program = Program::compile(&instructions);
+            for a in (0..5000).chain([729, 2024, 117440]) {
+                let registers = Registers { a, b: 0, c: 9 };
+                let mut computer = Computer::new(0, a, 0, 9, instructions.clone());
+                computer.run();
+
+                let mut output = Vec::new();
+                let after = program.run(registers, |o| {
+                    output.push(o);
+                    true
+                });
+                assert_eq!(output, computer.output);
+                assert_eq!(after, computer.registers());
+                assert!(program.outputs(registers, &computer.output));
+            }
+        }
+    }
+
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
@@ -347,4 +1031,41 @@ Program: 0,3,5,4,3,0";
         let result = part_two(input);
         assert_eq!(result, Some(117440));
     }
+
+    #[test]
+    fn test_find_quine_xor_program() {
+        let input = "Register A: 0
+Register B: 0
+Register C: 0
+
+Program: 2,4,1,1,7,5,4,6,0,3,1,4,5,5,3,0";
+        let (_, computer) = parse(input).unwrap();
+        let a = computer.find_quine().unwrap();
+        assert_eq!(computer.output_for(a), computer.instructions);
+    }
+
+    #[test]
+    fn test_find_quine_no_solution() {
+        let input = "Register A: 2024
+Register B: 0
+Register C: 0
+
+Program: 0,3,5,5,3,0";
+        let (_, computer) = parse(input).unwrap();
+        assert_eq!(computer.find_quine(), Err(QuineError::NoSolution));
+    }
+
+    #[test]
+    fn test_find_quine_unsupported() {
+        let input = "Register A: 2024
+Register B: 0
+Register C: 0
+
+Program: 0,1,5,4,3,0";
+        let (_, computer) = parse(input).unwrap();
+        assert!(matches!(
+            computer.find_quine(),
+            Err(QuineError::UnsupportedProgram(_))
+        ));
+    }
 }
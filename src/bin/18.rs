@@ -1,3 +1,5 @@
+use advent_of_code::bitgrid::BitGrid;
+use advent_of_code::union_find::UnionFind;
 use glam::IVec2;
 use itertools::Itertools;
 use nom::bytes::complete::tag;
@@ -9,39 +11,48 @@ use nom::IResult;
 use pathfinding::prelude::*;
 use std::ops::Not;
 
-advent_of_code::solution!(18);
-
-const MAP_SIZE: IVec2 = if cfg!(test) {
-    IVec2::new(7, 7)
-} else {
-    IVec2::new(71, 71)
-};
+advent_of_code::solution!(18, params: {
+    /// The width and height of the memory space.
+    size: i32 = 71,
+    /// The number of bytes that have fallen in part one.
+    bytes: usize = 1024,
+});
 
 fn parse_ivec2(input: &str) -> IResult<&str, IVec2> {
     let (input, (x, y)) = separated_pair(complete::i32, tag(","), complete::i32)(input)?;
     Ok((input, IVec2::new(x, y)))
 }
 
-const NUM_BYTES: usize = if cfg!(test) { 12 } else { 1024 };
+const DIRECTIONS: [IVec2; 4] = [IVec2::NEG_X, IVec2::X, IVec2::NEG_Y, IVec2::Y];
 
 struct Map {
+    map_size: IVec2,
     bytes: Vec<IVec2>,
-    fallen_bytes: usize,
+    fallen: BitGrid,
 }
 
 impl Map {
-    fn fallen_bytes(&self) -> &[IVec2] {
-        &self.bytes[..self.fallen_bytes]
+    fn start(&self) -> IVec2 {
+        IVec2::ZERO
     }
-    #[allow(unused)]
-    fn print_map(&self) {
-        self.print_path(&Vec::new())
+
+    fn exit(&self) -> IVec2 {
+        self.map_size - IVec2::ONE
+    }
+
+    /// Lets the first `n` bytes fall, clearing any bytes that fell before.
+    fn set_fallen_bytes(&mut self, n: usize) {
+        self.fallen.clear();
+        for byte in &self.bytes[..n] {
+            self.fallen.insert(*byte);
+        }
     }
 
+    #[allow(unused)]
     fn print_path(&self, path: &[IVec2]) {
-        for y in 0..MAP_SIZE.y {
-            for x in 0..MAP_SIZE.x {
-                if self.fallen_bytes().contains(&IVec2::new(x, y)) {
+        for y in 0..self.map_size.y {
+            for x in 0..self.map_size.x {
+                if self.fallen.contains(IVec2::new(x, y)) {
                     print!("#");
                 } else if path.contains(&IVec2::new(x, y)) {
                     print!("0");
@@ -55,81 +66,137 @@ impl Map {
 
     fn successors(&self, pos: &IVec2) -> Vec<IVec2> {
         // get the cells adj to pos
-        [(-1, 0), (1, 0), (0, -1), (0, 1)]
+        DIRECTIONS
             .into_iter()
-            .map(|(x, y)| pos + &IVec2::new(x, y))
+            .map(|d| pos + d)
             // check it's not a fallen byte '#' and not out of bounds
             .filter(|in_grid| {
-                self.fallen_bytes().contains(&in_grid).not()
-                    && in_grid.cmpge(IVec2::ZERO).all()
-                    && in_grid.cmplt(MAP_SIZE).all()
+                self.fallen.in_bounds(*in_grid) && self.fallen.contains(*in_grid).not()
             })
             .collect_vec()
     }
+
+    fn shortest_path(&self) -> Option<Vec<IVec2>> {
+        bfs(&self.start(), |p| self.successors(p), |p| *p == self.exit())
+    }
+
+    fn index(&self, pos: IVec2) -> usize {
+        (pos.y * self.map_size.x + pos.x) as usize
+    }
+
+    /// Finds the first byte after which the exit can no longer be reached, if there is one.
+    ///
+    /// Works backwards from the state where every byte has fallen: bytes are removed in reverse
+    /// order and each freed cell is joined with its free neighbours. The byte whose removal first
+    /// connects the start to the exit is the one that blocked it.
+    fn first_blocking_byte(&self) -> Option<IVec2> {
+        let mut fallen_at = vec![usize::MAX; (self.map_size.x * self.map_size.y) as usize];
+        for (i, byte) in self.bytes.iter().enumerate().rev() {
+            fallen_at[self.index(*byte)] = i;
+        }
+
+        let mut free = BitGrid::new(self.map_size);
+        let mut regions = UnionFind::new(fallen_at.len());
+        let mut free_cell = |free: &mut BitGrid, pos: IVec2| {
+            free.insert(pos);
+            for neighbour in DIRECTIONS.map(|d| pos + d) {
+                if free.contains(neighbour) {
+                    regions.union(self.index(pos), self.index(neighbour));
+                }
+            }
+            regions.connected(self.index(self.start()), self.index(self.exit()))
+        };
+
+        let mut connected = false;
+        for y in 0..self.map_size.y {
+            for x in 0..self.map_size.x {
+                let pos = IVec2::new(x, y);
+                if fallen_at[self.index(pos)] == usize::MAX {
+                    connected = free_cell(&mut free, pos);
+                }
+            }
+        }
+        if connected {
+            return None;
+        }
+
+        self.bytes
+            .iter()
+            .enumerate()
+            .rev()
+            // only the first byte to fall on a cell blocks it.
+            .filter(|(i, byte)| fallen_at[self.index(**byte)] == *i)
+            .find(|(_, byte)| free_cell(&mut free, **byte))
+            .map(|(_, byte)| *byte)
+    }
 }
 
-fn parse(input: &str) -> Map {
+fn parse(input: &str, map_size: IVec2, fallen_bytes: usize) -> Map {
     let (_, bytes) = separated_list1(line_ending, parse_ivec2)(input).unwrap();
-    Map {
+    let mut map = Map {
+        map_size,
         bytes,
-        fallen_bytes: NUM_BYTES,
-    }
+        fallen: BitGrid::new(map_size),
+    };
+    map.set_fallen_bytes(fallen_bytes);
+    map
 }
-pub fn part_one(input: &str) -> Option<u32> {
-    let map = parse(input);
 
-    let path = bfs(
-        &IVec2::ZERO,
-        |p| map.successors(p),
-        |p| p == &IVec2::new(MAP_SIZE.x - 1, MAP_SIZE.y - 1),
-    )
-    .expect("no path found");
-
-    map.print_path(&path);
+fn steps_to_exit(input: &str, map_size: IVec2, fallen_bytes: usize) -> Option<u32> {
+    let map = parse(input, map_size, fallen_bytes);
+    let path = map.shortest_path()?;
     Some(path.len() as u32 - 1)
 }
 
-pub fn part_two(input: &str) -> Option<String> {
-    let mut map = parse(input);
-    let mut first_known_success = map.fallen_bytes;
-    let mut last_known_failure = map.bytes.len();
-    let mut last_path = Vec::new();
-
-    let last_byte = loop {
-        if first_known_success + 1 == last_known_failure {
-            break map.bytes[last_known_failure - 1];
-        }
-        map.fallen_bytes = first_known_success + (last_known_failure - first_known_success) / 2;
-        match bfs(
-            &IVec2::ZERO,
-            |p| map.successors(p),
-            |p| p == &IVec2::new(MAP_SIZE.x - 1, MAP_SIZE.y - 1),
-        ) {
-            None => last_known_failure = map.fallen_bytes,
-            Some(p) => {
-                first_known_success = map.fallen_bytes;
-                last_path = p;
-            }
-        }
-    };
+fn first_blocking_byte(input: &str, map_size: IVec2) -> Option<String> {
+    let map = parse(input, map_size, 0);
+    let byte = map.first_blocking_byte()?;
+    Some(format!("{},{}", byte.x, byte.y))
+}
+
+pub fn part_one(input: &str) -> Option<u32> {
+    let params = params();
+    steps_to_exit(input, IVec2::splat(params.size), params.bytes)
+}
 
-    map.print_path(&last_path);
-    Some(format!("{},{}", last_byte.x, last_byte.y))
+pub fn part_two(input: &str) -> Option<String> {
+    first_blocking_byte(input, IVec2::splat(params().size))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::template::params::with_example;
+
+    const EXAMPLE_SIZE: IVec2 = IVec2::new(7, 7);
+
+    #[test]
+    fn test_first_blocking_byte_matches_bfs() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let mut map = parse(&input, EXAMPLE_SIZE, 0);
+        let blocking = (0..=map.bytes.len())
+            .find(|n| {
+                map.set_fallen_bytes(*n);
+                map.shortest_path().is_none()
+            })
+            .map(|n| map.bytes[n - 1]);
+        assert_eq!(map.first_blocking_byte(), blocking);
+    }
+
+    #[test]
+    fn test_never_blocked() {
+        assert_eq!(first_blocking_byte("1,1\n2,2\n", EXAMPLE_SIZE), None);
+    }
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let result = with_example(DAY, part_one);
         assert_eq!(result, Some(22));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY)).unwrap();
-        assert_eq!(result, "6,1");
+        let result = with_example(DAY, part_two);
+        assert_eq!(result, Some("6,1".to_string()));
     }
 }
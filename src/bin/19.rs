@@ -1,80 +1,182 @@
-use memoize::memoize;
+use itertools::Itertools;
 use nom::bytes::complete::tag;
 use nom::character::complete::{alpha1, line_ending};
 use nom::multi::separated_list1;
 use nom::sequence::{pair, preceded, terminated};
-use nom::{IResult, Parser};
+use nom::IResult;
 
-advent_of_code::solution!(19);
+advent_of_code::solution!(19, modes: {
+    "--explain" => explain,
+});
 
-fn parse(input: &str) -> IResult<&str, (Vec<String>, Vec<String>)> {
+fn parse(input: &str) -> IResult<&str, (Vec<&str>, Vec<&str>)> {
     pair(
-        terminated(
-            separated_list1(tag(", "), alpha1.map(String::from)),
-            line_ending,
-        ),
-        preceded(
-            line_ending,
-            separated_list1(line_ending, alpha1.map(String::from)),
-        ),
+        terminated(separated_list1(tag(", "), alpha1), line_ending),
+        preceded(line_ending, separated_list1(line_ending, alpha1)),
     )(input)
 }
 
-#[memoize]
-fn recursive_check(pattern: String, towels: Vec<String>) -> bool {
-    towels
-        .iter()
-        .map(|ref towel| {
-            pattern
-                .strip_prefix(towel.as_str())
-                .is_some_and(|r_pattern| {
-                    r_pattern.is_empty() || recursive_check(r_pattern.to_string(), towels.clone())
-                })
-        })
-        .any(|v| v)
+#[derive(Default)]
+struct Node {
+    children: [Option<usize>; 26],
+    /// Whether a towel ends at this node.
+    is_towel: bool,
 }
 
-#[memoize]
-fn recursive_find(pattern: String, towels: Vec<String>) -> usize {
-    towels
-        .iter()
-        .filter_map(|towel| {
-            pattern
-                .strip_prefix(towel.as_str())
-                .map(|r_pattern| {
-                    if r_pattern.is_empty() {
-                        Some(1)
-                    } else {
-                        Some(recursive_find(r_pattern.to_string(), towels.clone()))
+/// A trie of the available towel patterns.
+struct Towels {
+    nodes: Vec<Node>,
+}
+
+impl Towels {
+    fn new(towels: &[&str]) -> Self {
+        let mut trie = Towels {
+            nodes: vec![Node::default()],
+        };
+        for towel in towels {
+            let mut node = 0;
+            for b in towel.bytes() {
+                let child = (b - b'a') as usize;
+                node = match trie.nodes[node].children[child] {
+                    Some(next) => next,
+                    None => {
+                        trie.nodes.push(Node::default());
+                        let next = trie.nodes.len() - 1;
+                        trie.nodes[node].children[child] = Some(next);
+                        next
                     }
-                })
-                .flatten()
-        })
-        .sum()
+                };
+            }
+            trie.nodes[node].is_towel = true;
+        }
+        trie
+    }
+
+    /// The lengths of all towels that `pattern` starts with, shortest first.
+    fn prefixes<'a>(&'a self, pattern: &'a [u8]) -> impl Iterator<Item = usize> + 'a {
+        pattern
+            .iter()
+            .scan(0, |node, b| {
+                *node = self.nodes[*node].children[(b - b'a') as usize]?;
+                Some(*node)
+            })
+            .positions(|node| self.nodes[node].is_towel)
+            .map(|i| i + 1)
+    }
+
+    /// The number of ways to arrange towels into every suffix of `pattern`, indexed by the
+    /// suffix's offset. Entries are `None` if the count overflows an [`u64`].
+    fn suffix_arrangements(&self, pattern: &str) -> Vec<Option<u64>> {
+        let pattern = pattern.as_bytes();
+        let mut ways = vec![Some(0); pattern.len() + 1];
+        ways[pattern.len()] = Some(1);
+        for offset in (0..pattern.len()).rev() {
+            ways[offset] = self
+                .prefixes(&pattern[offset..])
+                .try_fold(0u64, |sum, len| sum.checked_add(ways[offset + len]?));
+        }
+        ways
+    }
+
+    /// The number of ways to arrange towels into `pattern`, or `None` if it overflows an [`u64`].
+    fn arrangements(&self, pattern: &str) -> Option<u64> {
+        self.suffix_arrangements(pattern)[0]
+    }
+
+    fn is_possible(&self, pattern: &str) -> bool {
+        self.arrangements(pattern) != Some(0)
+    }
+
+    /// One way to arrange towels into `pattern`, if there is any.
+    fn decompose<'a>(&self, pattern: &'a str) -> Option<Vec<&'a str>> {
+        let ways = self.suffix_arrangements(pattern);
+        let mut offset = 0;
+        let mut towels = vec![];
+        while offset < pattern.len() {
+            let len = self
+                .prefixes(&pattern.as_bytes()[offset..])
+                .find(|len| ways[offset + len] != Some(0))?;
+            towels.push(&pattern[offset..offset + len]);
+            offset += len;
+        }
+        Some(towels)
+    }
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
     let (_, (towels, patterns)) = parse(input).unwrap();
+    let towels = Towels::new(&towels);
     let n = patterns
         .iter()
-        .filter(|pattern| recursive_check(pattern.to_string(), towels.clone()))
+        .filter(|pattern| towels.is_possible(pattern))
         .count();
     Some(n as u32)
 }
 
 pub fn part_two(input: &str) -> Option<u64> {
     let (_, (towels, patterns)) = parse(input).unwrap();
-    let n: usize = patterns
-        .iter()
-        .map(|pattern| recursive_find(pattern.to_string(), towels.clone()))
-        .sum();
-    Some(n as u64)
+    let towels = Towels::new(&towels);
+    patterns.iter().try_fold(0u64, |sum, pattern| {
+        sum.checked_add(towels.arrangements(pattern)?)
+    })
+}
+
+/// Prints one arrangement for every possible design.
+fn explain(input: &str) {
+    let (_, (towels, patterns)) = parse(input).unwrap();
+    let towels = Towels::new(&towels);
+    for pattern in patterns {
+        match towels.decompose(pattern) {
+            Some(decomposition) => println!("{pattern}: {}", decomposition.join(" ")),
+            None => println!("{pattern}: impossible"),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_arrangements() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let (_, (towels, patterns)) = parse(&input).unwrap();
+        let towels = Towels::new(&towels);
+        let counts = patterns
+            .iter()
+            .map(|p| towels.arrangements(p).unwrap())
+            .collect_vec();
+        assert_eq!(counts, vec![2, 1, 4, 6, 0, 1, 2, 0]);
+    }
+
+    #[test]
+    fn test_decompose() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let (_, (towels, patterns)) = parse(&input).unwrap();
+        let trie = Towels::new(&towels);
+        for pattern in patterns {
+            match trie.decompose(pattern) {
+                Some(decomposition) => {
+                    assert_eq!(decomposition.concat(), pattern);
+                    assert!(decomposition.iter().all(|t| towels.contains(t)));
+                }
+                None => assert!(!trie.is_possible(pattern)),
+            }
+        }
+    }
+
+    #[test]
+    fn test_overflow() {
+        let towels = Towels::new(&["a", "aa"]);
+        // the number of arrangements is a fibonacci number, which exceeds u64 after 93.
+        assert_eq!(
+            towels.arrangements(&"a".repeat(91)),
+            Some(7540113804746346429)
+        );
+        assert_eq!(towels.arrangements(&"a".repeat(100)), None);
+        assert!(towels.is_possible(&"a".repeat(100)));
+    }
+
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
@@ -1,15 +1,22 @@
+use advent_of_code::bitgrid::BitGrid;
+use advent_of_code::template::stress::Rng;
 use glam::IVec2;
 use itertools::Itertools;
-use pathfinding::num_traits::CheckedSub;
-use pathfinding::prelude::{bfs, dijkstra};
-use std::collections::{HashMap, VecDeque};
+use pathfinding::prelude::bfs;
+use std::collections::{BTreeMap, VecDeque};
 use std::ops::{AddAssign, Not};
 
-advent_of_code::solution!(20);
+advent_of_code::solution!(20, params: {
+    /// The number of picoseconds a cheat has to save to be counted.
+    min_saving: usize = 100,
+}, generator: generate, variants: {
+    1 => part_one_along_track,
+    2 => part_two_along_track,
+});
 
 struct Map {
     map_size: IVec2,
-    walls: Vec<IVec2>,
+    walls: BitGrid,
     start: IVec2,
     end: IVec2,
 }
@@ -20,27 +27,79 @@ impl Map {
             .iter()
             .flat_map(|m| {
                 let next = pos + m;
-                self.walls.contains(&next).not().then_some(next)
+                self.walls.contains(next).not().then_some(next)
             })
             .collect()
     }
 
-    fn jumpable(&self, pos: &IVec2) -> Vec<IVec2> {
-        [IVec2::X, -IVec2::X, IVec2::Y, -IVec2::Y]
-            .iter()
-            .flat_map(|m| {
-                let next = pos + m;
-                let next_jump = next + m;
-                (self.walls.contains(&next) && self.walls.contains(&next_jump).not())
-                    .then_some(next_jump)
-            })
-            .collect()
+    fn index(&self, pos: &IVec2) -> Option<usize> {
+        (pos.cmpge(IVec2::ZERO).all() && pos.cmplt(self.map_size).all())
+            .then(|| (pos.y * self.map_size.x + pos.x) as usize)
     }
+
+    /// The number of picoseconds it takes to get from `origin` to each cell without cheating,
+    /// indexed by [`Map::index`]. Walls and unreachable cells are `None`.
+    fn distances_from(&self, origin: IVec2) -> Vec<Option<usize>> {
+        let mut distances = vec![None; (self.map_size.x * self.map_size.y) as usize];
+        distances[self.index(&origin).unwrap()] = Some(0);
+        let mut queue = VecDeque::from([origin]);
+        while let Some(pos) = queue.pop_front() {
+            let distance = distances[self.index(&pos).unwrap()].unwrap();
+            for next in self.successors(&pos) {
+                let Some(i) = self.index(&next) else {
+                    continue;
+                };
+                if distances[i].is_none() {
+                    distances[i] = Some(distance + 1);
+                    queue.push_back(next);
+                }
+            }
+        }
+        distances
+    }
+
+    /// Counts the cheats that save at least `min_saving` picoseconds, grouped by the time they save.
+    ///
+    /// A cheat moves through walls from one track cell to another at most `max_cheat` steps
+    /// away, measured in manhattan distance. The time it saves is the honest race time minus the
+    /// time to reach the cheat's start, its length and the time from its end to the finish.
+    fn cheat_savings(&self, max_cheat: i32, min_saving: usize) -> BTreeMap<usize, usize> {
+        let from_start = self.distances_from(self.start);
+        let to_end = self.distances_from(self.end);
+        let Some(race_time) = from_start[self.index(&self.end).unwrap()] else {
+            return BTreeMap::new();
+        };
+        let mut savings: BTreeMap<usize, usize> = BTreeMap::new();
+        for (i, from) in from_start.iter().enumerate() {
+            let Some(from) = from else {
+                continue;
+            };
+            let pos = IVec2::new(i as i32 % self.map_size.x, i as i32 / self.map_size.x);
+            for dy in -max_cheat..=max_cheat {
+                let max_dx = max_cheat - dy.abs();
+                for dx in -max_dx..=max_dx {
+                    let Some(to) = self
+                        .index(&(pos + IVec2::new(dx, dy)))
+                        .and_then(|j| to_end[j])
+                    else {
+                        continue;
+                    };
+                    let cheat_time = from + (dx.abs() + dy.abs()) as usize + to;
+                    let saving = race_time.saturating_sub(cheat_time);
+                    if saving > 0 && saving >= min_saving {
+                        savings.entry(saving).or_default().add_assign(1);
+                    }
+                }
+            }
+        }
+        savings
+    }
+
     #[allow(unused)]
     fn print_path(&self, path: &[IVec2]) {
         for y in 0..self.map_size.y {
             for x in 0..self.map_size.x {
-                if self.walls.contains(&IVec2::new(x, y)) {
+                if self.walls.contains(IVec2::new(x, y)) {
                     print!("#");
                 } else if path.contains(&IVec2::new(x, y)) {
                     print!("0");
@@ -54,15 +113,17 @@ impl Map {
 }
 
 fn parse(input: &str) -> Map {
-    let walls = input
-        .split_whitespace()
-        .enumerate()
-        .flat_map(|(y, line)| {
+    let height = input.split_whitespace().count();
+    let width = input.split_whitespace().next().unwrap().len();
+    let map_size = IVec2::new(width as i32, height as i32);
+    let walls = BitGrid::from_positions(
+        map_size,
+        input.split_whitespace().enumerate().flat_map(|(y, line)| {
             line.char_indices()
                 .filter(|(_, c)| c == &'#')
                 .map(move |(x, _)| IVec2::new(x as i32, y as i32))
-        })
-        .collect_vec();
+        }),
+    );
     let start = input
         .split_whitespace()
         .enumerate()
@@ -83,88 +144,158 @@ fn parse(input: &str) -> Map {
         })
         .exactly_one()
         .unwrap();
-    let height = input.split_whitespace().count();
-    let width = input.split_whitespace().next().unwrap().len();
     Map {
-        map_size: IVec2::new(width as i32, height as i32),
+        map_size,
         walls,
         start,
         end,
     }
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let map = parse(input);
-    let path = bfs(&map.start, |(p)| map.successors(p), |p| p == &map.end).unwrap();
-    let mut paths: HashMap<usize, usize> = HashMap::new();
-    for (i, p) in path.iter().enumerate() {
-        map.jumpable(p)
+/// A single winding racetrack through a grid of `size` by `size` rooms.
+///
+/// The track is the path between two rooms of a random maze, with all other rooms walled off,
+/// since the puzzle guarantees there is only a single path from the start to the end.
+fn generate(seed: u64, size: usize) -> String {
+    let mut rng = Rng::new(seed);
+    let width = 2 * size.max(2) + 1;
+    let rooms = (0..width / 2)
+        .cartesian_product(0..width / 2)
+        .map(|(x, y)| (2 * x + 1, 2 * y + 1))
+        .collect_vec();
+    let start = *rng.choose(&rooms);
+    let mut parents = vec![vec![None; width]; width];
+    parents[start.1][start.0] = Some(start);
+    let mut stack = vec![start];
+    while let Some(&(x, y)) = stack.last() {
+        let unvisited = [(2, 0), (0, 2), (-2, 0), (0, -2)]
             .iter()
-            .flat_map(|j| {
-                path.iter()
-                    .position(|p| p == j)
-                    // subtracting 2 for the cheat length
-                    .and_then(|less| less.checked_sub(i)?.checked_sub(2))
-            })
-            .for_each(|len| {
-                paths.entry(len).or_default().add_assign(1);
-            });
+            .map(|(dx, dy)| (x as i32 + dx, y as i32 + dy))
+            .filter(|&(x, y)| x > 0 && y > 0 && x < width as i32 && y < width as i32)
+            .map(|(x, y)| (x as usize, y as usize))
+            .filter(|&(x, y)| parents[y][x].is_none())
+            .collect_vec();
+        if unvisited.is_empty() {
+            stack.pop();
+            continue;
+        }
+        let (nx, ny) = *rng.choose(&unvisited);
+        parents[ny][nx] = Some((x, y));
+        stack.push((nx, ny));
     }
 
-    for (len, count) in paths.iter().sorted_by_key(|(len, _)| *len) {
-        if len == &1 {
-            println!("There is one cheat that saves {} picoseconds.", count);
-        } else {
-            println!("There are {} cheats that save {} picoseconds.", count, len);
-        }
+    let mut grid = vec![vec!['#'; width]; width];
+    let end = *rng.choose(&rooms[1..]);
+    let end = if end == start { rooms[0] } else { end };
+    let mut room = end;
+    while room != start {
+        let parent = parents[room.1][room.0].unwrap();
+        grid[room.1][room.0] = '.';
+        grid[(room.1 + parent.1) / 2][(room.0 + parent.0) / 2] = '.';
+        room = parent;
     }
+    grid[start.1][start.0] = 'S';
+    grid[end.1][end.0] = 'E';
+    grid.iter().map(|row| row.iter().join("") + "\n").collect()
+}
 
-    let over_100 = paths
-        .iter()
-        .filter(|(len, count)| len >= &&100)
-        .map(|(_, count)| *count as u32)
-        .sum();
-    Some(over_100)
+pub fn part_one(input: &str) -> Option<u32> {
+    let map = parse(input);
+    Some(
+        map.cheat_savings(2, params().min_saving)
+            .values()
+            .sum::<usize>() as u32,
+    )
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
     let map = parse(input);
-    let path = bfs(&map.start, |(p)| map.successors(p), |p| p == &map.end).unwrap();
-    let mut paths: HashMap<usize, usize> = HashMap::new();
-    let base_cost = path.len();
+    Some(
+        map.cheat_savings(20, params().min_saving)
+            .values()
+            .sum::<usize>() as u32,
+    )
+}
 
-    // borrowed from https://github.com/ChristopherBiscardi/advent-of-code/blob/main/2024/rust/day-20/src/part2.rs :)
-    let result = path
+/// Counts the cheats by comparing every pair of positions on the single racetrack.
+fn cheats_along_track(input: &str, max_cheat: usize) -> Option<u32> {
+    let map = parse(input);
+    let min_saving = params().min_saving;
+    let track = bfs(&map.start, |p| map.successors(p), |p| p == &map.end)?;
+    let cheats = track
         .iter()
         .enumerate()
         .tuple_combinations()
-        .filter_map(|((start_cost, start_pos), (end_cost, end_pos))| {
-            let distance: usize = (start_pos - end_pos).abs().element_sum() as usize;
-            if distance > 20 {
-                return None;
-            };
-            let cheat_cost = start_cost + distance + base_cost - end_cost;
-            Some(base_cost - cheat_cost)
+        .filter(|((from, a), (to, b))| {
+            let distance = (*a - *b).abs().element_sum() as usize;
+            distance <= max_cheat && to - from >= distance + min_saving
         })
-        .filter(|savings| savings >= &100)
         .count();
+    Some(cheats as u32)
+}
 
-    Some(result as u32)
+pub fn part_one_along_track(input: &str) -> Option<u32> {
+    cheats_along_track(input, 2)
+}
+
+pub fn part_two_along_track(input: &str) -> Option<u32> {
+    cheats_along_track(input, 20)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::template::params::with_example;
+
+    #[test]
+    fn test_cheat_savings() {
+        let map = parse(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(
+            map.cheat_savings(2, 1),
+            BTreeMap::from([
+                (2, 14),
+                (4, 14),
+                (6, 2),
+                (8, 4),
+                (10, 2),
+                (12, 3),
+                (20, 1),
+                (36, 1),
+                (38, 1),
+                (40, 1),
+                (64, 1),
+            ])
+        );
+        assert_eq!(
+            map.cheat_savings(20, 50),
+            BTreeMap::from([
+                (50, 32),
+                (52, 31),
+                (54, 29),
+                (56, 39),
+                (58, 25),
+                (60, 23),
+                (62, 20),
+                (64, 19),
+                (66, 12),
+                (68, 14),
+                (70, 12),
+                (72, 22),
+                (74, 4),
+                (76, 3),
+            ])
+        );
+    }
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(0));
+        let result = with_example(DAY, part_one);
+        assert_eq!(result, Some(1));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        let result = with_example(DAY, part_two);
+        assert_eq!(result, Some(285));
     }
 }
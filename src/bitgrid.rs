@@ -0,0 +1,157 @@
+//! A dense set of grid positions.
+use glam::IVec2;
+
+/// A set of positions on a fixed-size grid, stored as one bit per cell.
+///
+/// Lookups are O(1). Positions outside of the grid are never contained.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BitGrid {
+    size: IVec2,
+    words: Vec<u64>,
+}
+
+impl BitGrid {
+    /// Creates an empty grid of `size.x` columns and `size.y` rows.
+    pub fn new(size: IVec2) -> Self {
+        assert!(size.cmpge(IVec2::ZERO).all(), "invalid grid size {size}");
+        let cells = size.x as usize * size.y as usize;
+        Self {
+            size,
+            words: vec![0; cells.div_ceil(64)],
+        }
+    }
+
+    /// Creates a grid of the given size with every position of `positions` set.
+    pub fn from_positions(size: IVec2, positions: impl IntoIterator<Item = IVec2>) -> Self {
+        let mut grid = Self::new(size);
+        for pos in positions {
+            grid.insert(pos);
+        }
+        grid
+    }
+
+    pub fn size(&self) -> IVec2 {
+        self.size
+    }
+
+    pub fn in_bounds(&self, pos: IVec2) -> bool {
+        pos.cmpge(IVec2::ZERO).all() && pos.cmplt(self.size).all()
+    }
+
+    fn index(&self, pos: IVec2) -> Option<(usize, u64)> {
+        self.in_bounds(pos).then(|| {
+            let i = pos.y as usize * self.size.x as usize + pos.x as usize;
+            (i / 64, 1 << (i % 64))
+        })
+    }
+
+    pub fn contains(&self, pos: IVec2) -> bool {
+        self.index(pos)
+            .is_some_and(|(word, bit)| self.words[word] & bit != 0)
+    }
+
+    /// Sets a position, returning whether it was not set before.
+    ///
+    /// # Panics
+    /// Panics if the position is outside of the grid.
+    pub fn insert(&mut self, pos: IVec2) -> bool {
+        let (word, bit) = self
+            .index(pos)
+            .unwrap_or_else(|| panic!("position {pos} is outside of the grid"));
+        let was_set = self.words[word] & bit != 0;
+        self.words[word] |= bit;
+        !was_set
+    }
+
+    /// Clears a position, returning whether it was set before.
+    pub fn remove(&mut self, pos: IVec2) -> bool {
+        let Some((word, bit)) = self.index(pos) else {
+            return false;
+        };
+        let was_set = self.words[word] & bit != 0;
+        self.words[word] &= !bit;
+        was_set
+    }
+
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    /// The number of set positions.
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|w| *w == 0)
+    }
+
+    /// Iterates over the set positions in reading order.
+    pub fn iter(&self) -> impl Iterator<Item = IVec2> + '_ {
+        let width = self.size.x as usize;
+        self.words.iter().enumerate().flat_map(move |(word, bits)| {
+            let mut bits = *bits;
+            std::iter::from_fn(move || {
+                if bits == 0 {
+                    return None;
+                }
+                let i = word * 64 + bits.trailing_zeros() as usize;
+                bits &= bits - 1;
+                Some(IVec2::new((i % width) as i32, (i / width) as i32))
+            })
+        })
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::BitGrid;
+    use glam::IVec2;
+
+    #[test]
+    fn inserts_and_removes() {
+        let mut grid = BitGrid::new(IVec2::new(71, 71));
+        assert!(grid.is_empty());
+        assert!(grid.insert(IVec2::new(70, 70)));
+        assert!(!grid.insert(IVec2::new(70, 70)));
+        assert!(grid.insert(IVec2::new(0, 1)));
+        assert!(grid.contains(IVec2::new(70, 70)));
+        assert!(grid.contains(IVec2::new(0, 1)));
+        assert!(!grid.contains(IVec2::new(1, 0)));
+        assert_eq!(grid.len(), 2);
+        assert!(grid.remove(IVec2::new(70, 70)));
+        assert!(!grid.remove(IVec2::new(70, 70)));
+        assert_eq!(grid.len(), 1);
+        grid.clear();
+        assert!(grid.is_empty());
+    }
+
+    #[test]
+    fn handles_out_of_bounds_positions() {
+        let grid = BitGrid::from_positions(IVec2::new(3, 2), [IVec2::new(2, 1)]);
+        assert!(!grid.contains(IVec2::new(3, 0)));
+        assert!(!grid.contains(IVec2::new(-1, 1)));
+        assert!(!grid.contains(IVec2::new(0, 2)));
+        assert!(!grid.in_bounds(IVec2::new(0, -1)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_when_inserting_out_of_bounds() {
+        let mut grid = BitGrid::new(IVec2::new(3, 3));
+        grid.insert(IVec2::new(3, 0));
+    }
+
+    #[test]
+    fn iterates_in_reading_order() {
+        let positions = [
+            IVec2::new(5, 0),
+            IVec2::new(63, 0),
+            IVec2::new(0, 1),
+            IVec2::new(99, 1),
+            IVec2::new(27, 2),
+        ];
+        let grid = BitGrid::from_positions(IVec2::new(100, 3), positions.iter().rev().copied());
+        assert_eq!(grid.iter().collect::<Vec<_>>(), positions);
+    }
+}
@@ -0,0 +1,127 @@
+//! Helpers for directed acyclic graphs.
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// Counts the distinct paths from a node to any node for which `success` returns `true`.
+///
+/// Path counts are memoised per node, so repeated calls with different start nodes only explore
+/// the parts of the graph that have not been counted yet. A path ends at the first success node
+/// it reaches.
+///
+/// ```
+/// # use advent_of_code::dag::PathCounter;
+/// // 1 -> 2 -> 4 and 1 -> 3 -> 4
+/// let mut counter = PathCounter::new(
+///     |n: &u32| match n {
+///         1 => vec![2, 3],
+///         2 | 3 => vec![4],
+///         _ => vec![],
+///     },
+///     |n| *n == 4,
+/// );
+/// assert_eq!(counter.count(&1), 2);
+/// ```
+pub struct PathCounter<N, FN, FS> {
+    successors: FN,
+    success: FS,
+    counts: HashMap<N, u64>,
+}
+
+impl<N, FN, IN, FS> PathCounter<N, FN, FS>
+where
+    N: Eq + Hash + Clone,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = N>,
+    FS: FnMut(&N) -> bool,
+{
+    pub fn new(successors: FN, success: FS) -> Self {
+        Self {
+            successors,
+            success,
+            counts: HashMap::new(),
+        }
+    }
+
+    /// Returns the number of paths from `start` to a success node.
+    ///
+    /// # Panics
+    /// Panics if a cycle is reachable from `start` or the count overflows an [`u64`].
+    pub fn count(&mut self, start: &N) -> u64 {
+        let mut in_progress: HashSet<N> = HashSet::new();
+        // NOTE: nodes are visited iteratively in post-order, so that deep graphs do not overflow the stack.
+        let mut stack = vec![(start.clone(), false)];
+
+        while let Some((node, expanded)) = stack.pop() {
+            if self.counts.contains_key(&node) {
+                continue;
+            }
+            if (self.success)(&node) {
+                self.counts.insert(node, 1);
+                continue;
+            }
+            if expanded {
+                let count = (self.successors)(&node)
+                    .into_iter()
+                    .map(|successor| self.counts[&successor])
+                    .try_fold(0u64, u64::checked_add)
+                    .expect("path count overflows u64");
+                in_progress.remove(&node);
+                self.counts.insert(node, count);
+            } else {
+                in_progress.insert(node.clone());
+                stack.push((node.clone(), true));
+                for successor in (self.successors)(&node) {
+                    assert!(!in_progress.contains(&successor), "graph contains a cycle");
+                    if !self.counts.contains_key(&successor) {
+                        stack.push((successor, false));
+                    }
+                }
+            }
+        }
+
+        self.counts[start]
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::PathCounter;
+
+    #[test]
+    fn counts_diamonds() {
+        // a chain of 20 diamonds has 2^20 paths.
+        let mut counter = PathCounter::new(
+            |n: &u32| match n % 3 {
+                0 => vec![n + 1, n + 2],
+                _ => vec![n + (3 - n % 3)],
+            },
+            |n| *n == 60,
+        );
+        assert_eq!(counter.count(&0), 1 << 20);
+        assert_eq!(counter.count(&57), 2);
+        assert_eq!(counter.count(&60), 1);
+    }
+
+    #[test]
+    fn counts_dead_ends() {
+        let mut counter = PathCounter::new(
+            |n: &u32| if *n < 5 { vec![n + 1, 10] } else { vec![] },
+            |n| *n == 5,
+        );
+        assert_eq!(counter.count(&0), 1);
+        assert_eq!(counter.count(&10), 0);
+    }
+
+    #[test]
+    fn handles_deep_graphs() {
+        let mut counter = PathCounter::new(|n: &u32| [n + 1], |n| *n == 100_000);
+        assert_eq!(counter.count(&0), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_cycles() {
+        let mut counter = PathCounter::new(|n: &u32| [(n + 1) % 3], |n| *n == 5);
+        counter.count(&0);
+    }
+}
@@ -1,3 +1,6 @@
+pub mod bitgrid;
+pub mod dag;
 pub mod template;
+pub mod union_find;
 
 // Use this file to add helper functions and additional modules.
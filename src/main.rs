@@ -1,18 +1,22 @@
-use advent_of_code::template::commands::{all, download, read, scaffold, solve, time};
+use advent_of_code::template::commands::{
+    all, download, inputs, read, scaffold, solve, status, stress, time, watch,
+};
+use advent_of_code::template::config::Config;
 use args::{parse, AppArguments};
 
 #[cfg(feature = "today")]
-use advent_of_code::template::Day;
-#[cfg(feature = "today")]
-use std::process;
+use advent_of_code::template::commands::today;
 
 mod args {
+    use advent_of_code::template::commands::inputs;
     use advent_of_code::template::Day;
+    use std::path::PathBuf;
     use std::process;
 
     pub enum AppArguments {
         Download {
             day: Day,
+            force: bool,
         },
         Read {
             day: Day,
@@ -27,26 +31,56 @@ mod args {
             release: bool,
             dhat: bool,
             submit: Option<u8>,
+            watch: bool,
+            tests: bool,
+            args: Vec<String>,
+        },
+        Stress {
+            day: Day,
+            args: Vec<String>,
         },
         All {
             release: bool,
         },
+        Status {
+            quick: bool,
+        },
+        Inputs {
+            action: inputs::Action,
+            key_file: Option<PathBuf>,
+            force: bool,
+        },
         Time {
             all: bool,
             day: Option<Day>,
             store: bool,
         },
         #[cfg(feature = "today")]
-        Today,
+        Today {
+            wait: bool,
+            year: Option<u16>,
+        },
     }
 
     pub fn parse() -> Result<AppArguments, Box<dyn std::error::Error>> {
         let mut args = pico_args::Arguments::from_env();
 
-        let app_args = match args.subcommand()?.as_deref() {
+        let mut app_args = match args.subcommand()?.as_deref() {
             Some("all") => AppArguments::All {
                 release: args.contains("--release"),
             },
+            Some("status") => AppArguments::Status {
+                quick: args.contains("--quick"),
+            },
+            Some("inputs") => {
+                let key_file = args.opt_value_from_str("--key-file")?;
+                let force = args.contains("--force");
+                AppArguments::Inputs {
+                    action: args.free_from_str()?,
+                    key_file,
+                    force,
+                }
+            }
             Some("time") => {
                 let all = args.contains("--all");
                 let store = args.contains("--store");
@@ -59,6 +93,7 @@ mod args {
             }
             Some("download") => AppArguments::Download {
                 day: args.free_from_str()?,
+                force: args.contains("--force"),
             },
             Some("read") => AppArguments::Read {
                 day: args.free_from_str()?,
@@ -73,9 +108,19 @@ mod args {
                 release: args.contains("--release"),
                 submit: args.opt_value_from_str("--submit")?,
                 dhat: args.contains("--dhat"),
+                watch: args.contains("--watch"),
+                tests: args.contains("--test"),
+                args: vec![],
+            },
+            Some("stress") => AppArguments::Stress {
+                day: args.free_from_str()?,
+                args: vec![],
             },
             #[cfg(feature = "today")]
-            Some("today") => AppArguments::Today,
+            Some("today") => AppArguments::Today {
+                wait: args.contains("--wait"),
+                year: args.opt_value_from_str("--year")?,
+            },
             Some(x) => {
                 eprintln!("Unknown command: {x}");
                 process::exit(1);
@@ -87,7 +132,11 @@ mod args {
         };
 
         let remaining = args.finish();
-        if !remaining.is_empty() {
+        if let AppArguments::Solve { args, .. } | AppArguments::Stress { args, .. } = &mut app_args
+        {
+            // forward remaining arguments (e.g. `--debug`) to the solution binary.
+            args.extend(remaining.iter().map(|x| x.to_string_lossy().into_owned()));
+        } else if !remaining.is_empty() {
             eprintln!("Warning: unknown argument(s): {remaining:?}.");
         }
 
@@ -96,6 +145,11 @@ mod args {
 }
 
 fn main() {
+    if let Err(e) = Config::load() {
+        eprintln!("Error: {e}");
+        std::process::exit(1);
+    }
+
     match parse() {
         Err(err) => {
             eprintln!("Error: {err}");
@@ -103,8 +157,14 @@ fn main() {
         }
         Ok(args) => match args {
             AppArguments::All { release } => all::handle(release),
+            AppArguments::Status { quick } => status::handle(quick),
+            AppArguments::Inputs {
+                action,
+                key_file,
+                force,
+            } => inputs::handle(action, key_file.as_deref(), force),
             AppArguments::Time { day, all, store } => time::handle(day, all, store),
-            AppArguments::Download { day } => download::handle(day),
+            AppArguments::Download { day, force } => download::handle(day, force),
             AppArguments::Read { day } => read::handle(day),
             AppArguments::Scaffold {
                 day,
@@ -113,7 +173,7 @@ fn main() {
             } => {
                 scaffold::handle(day, overwrite);
                 if download {
-                    download::handle(day);
+                    download::handle(day, false);
                 }
             }
             AppArguments::Solve {
@@ -121,24 +181,34 @@ fn main() {
                 release,
                 dhat,
                 submit,
-            } => solve::handle(day, release, dhat, submit),
-            #[cfg(feature = "today")]
-            AppArguments::Today => {
-                match Day::today() {
-                    Some(day) => {
-                        scaffold::handle(day, false);
-                        download::handle(day);
-                        read::handle(day)
-                    }
-                    None => {
-                        eprintln!(
-                            "`today` command can only be run between the 1st and \
-                            the 25th of december. Please use `scaffold` with a specific day."
-                        );
-                        process::exit(1)
-                    }
-                };
+                watch: true,
+                tests,
+                args,
+            } => {
+                if dhat || submit.is_some() {
+                    eprintln!("`--watch` cannot be combined with `--dhat` or `--submit`.");
+                    std::process::exit(1);
+                }
+                watch::handle(day, release, tests, &args)
             }
+            AppArguments::Solve {
+                day,
+                release,
+                dhat,
+                submit,
+                watch: false,
+                tests,
+                args,
+            } => {
+                if tests {
+                    eprintln!("`--test` is only supported with `--watch`.");
+                    std::process::exit(1);
+                }
+                solve::handle(day, release, dhat, submit, &args)
+            }
+            AppArguments::Stress { day, args } => stress::handle(day, &args),
+            #[cfg(feature = "today")]
+            AppArguments::Today { wait, year } => today::handle(wait, year),
         },
     };
 }
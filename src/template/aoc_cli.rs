@@ -1,16 +1,24 @@
 /// Wrapper module around the "aoc-cli" command-line.
 use std::{
     fmt::Display,
+    fs,
     process::{Command, Output, Stdio},
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
-use crate::template::Day;
+use crate::template::{config, Day};
+
+/// The minimum time between two requests to the Advent of Code server.
+const MIN_REQUEST_INTERVAL: Duration = Duration::from_secs(5);
 
 #[derive(Debug)]
 pub enum AocCommandError {
     CommandNotFound,
     CommandNotCallable,
     BadExitStatus(Output),
+    /// The response to a submission didn't match any known outcome.
+    UnexpectedResponse(String),
 }
 
 impl Display for AocCommandError {
@@ -21,10 +29,114 @@ impl Display for AocCommandError {
             AocCommandError::BadExitStatus(_) => {
                 write!(f, "aoc-cli exited with a non-zero status.")
             }
+            AocCommandError::UnexpectedResponse(response) => {
+                write!(f, "unexpected response from adventofcode.com:\n{response}")
+            }
+        }
+    }
+}
+
+/// The result of [`download`].
+#[derive(Debug)]
+pub enum Download {
+    Downloaded(Output),
+    /// The input already had content and the download wasn't forced.
+    Cached,
+}
+
+/// The outcome of [`submit`], parsed from the response of adventofcode.com.
+#[derive(Debug, PartialEq, Eq)]
+pub enum SubmissionOutcome {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// The answer is wrong, but the response doesn't say in which direction.
+    Wrong,
+    /// An answer was submitted too recently, the next one is accepted after the duration.
+    RateLimited(Duration),
+    /// The part was already solved, or part one wasn't solved before submitting part two.
+    AlreadySolved,
+}
+
+impl Display for SubmissionOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SubmissionOutcome::Correct => write!(f, "⭐ That's the right answer!"),
+            SubmissionOutcome::TooHigh => {
+                write!(f, "✖ That's not the right answer, it is too high.")
+            }
+            SubmissionOutcome::TooLow => {
+                write!(f, "✖ That's not the right answer, it is too low.")
+            }
+            SubmissionOutcome::Wrong => write!(f, "✖ That's not the right answer."),
+            SubmissionOutcome::RateLimited(wait) => {
+                let secs = wait.as_secs();
+                write!(
+                    f,
+                    "⏳ An answer was submitted too recently, try again in {}m {}s.",
+                    secs / 60,
+                    secs % 60
+                )
+            }
+            SubmissionOutcome::AlreadySolved => write!(
+                f,
+                "This part is already solved, or part one has to be solved first."
+            ),
         }
     }
 }
 
+/// Parses a wait time like `1h 4m 32s` or `32s`.
+fn parse_wait(wait: &str) -> Option<Duration> {
+    let mut secs = 0;
+    for token in wait.split_whitespace() {
+        let unit = match token.chars().last()? {
+            'h' => 3600,
+            'm' => 60,
+            's' => 1,
+            _ => return None,
+        };
+        let value: u64 = token[..token.len() - 1].parse().ok()?;
+        secs += value * unit;
+    }
+    Some(Duration::from_secs(secs))
+}
+
+/// Parses the response aoc-cli prints for a submission. `None` if it isn't a known response.
+pub fn parse_submission(response: &str) -> Option<SubmissionOutcome> {
+    // aoc-cli wraps the response to the terminal width and may color it.
+    let mut text = String::new();
+    let mut chars = response.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            chars.by_ref().find(|c| c.is_ascii_alphabetic());
+        } else {
+            text.push(c);
+        }
+    }
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    if text.contains("That's the right answer") {
+        Some(SubmissionOutcome::Correct)
+    } else if text.contains("You don't seem to be solving the right level") {
+        Some(SubmissionOutcome::AlreadySolved)
+    } else if text.contains("You gave an answer too recently") {
+        let (_, wait) = text.split_once("You have ")?;
+        let (wait, _) = wait.split_once(" left to wait")?;
+        parse_wait(wait).map(SubmissionOutcome::RateLimited)
+    } else if text.contains("That's not the right answer") {
+        if text.contains("your answer is too high") {
+            Some(SubmissionOutcome::TooHigh)
+        } else if text.contains("your answer is too low") {
+            Some(SubmissionOutcome::TooLow)
+        } else {
+            Some(SubmissionOutcome::Wrong)
+        }
+    } else {
+        None
+    }
+}
+
 pub fn check() -> Result<(), AocCommandError> {
     Command::new("aoc")
         .arg("-V")
@@ -33,7 +145,8 @@ pub fn check() -> Result<(), AocCommandError> {
     Ok(())
 }
 
-pub fn read(day: Day) -> Result<Output, AocCommandError> {
+/// Reads the puzzle of `day` of the event in `year`, or of the latest event if `year` is `None`.
+pub fn read(day: Day, year: Option<u16>) -> Result<Output, AocCommandError> {
     let puzzle_path = get_puzzle_path(day);
 
     let args = build_args(
@@ -44,13 +157,22 @@ pub fn read(day: Day) -> Result<Output, AocCommandError> {
             puzzle_path,
         ],
         day,
+        year,
     );
 
     call_aoc_cli(&args)
 }
 
-pub fn download(day: Day) -> Result<Output, AocCommandError> {
+/// Downloads the input and puzzle of `day` of the event in `year`, or of the latest event if
+/// `year` is `None`. An input that already has content is only downloaded again if `force` is
+/// set.
+pub fn download(day: Day, year: Option<u16>, force: bool) -> Result<Download, AocCommandError> {
     let input_path = get_input_path(day);
+    let has_input = fs::metadata(&input_path).is_ok_and(|metadata| metadata.len() > 0);
+    if has_input && !force {
+        println!("🎄 Input \"{input_path}\" already exists, skipping the download. Use `--force` to download it again.");
+        return Ok(Download::Cached);
+    }
     let puzzle_path = get_puzzle_path(day);
 
     let args = build_args(
@@ -63,42 +185,61 @@ pub fn download(day: Day) -> Result<Output, AocCommandError> {
             puzzle_path.to_string(),
         ],
         day,
+        year,
     );
 
     let output = call_aoc_cli(&args)?;
     println!("---");
     println!("🎄 Successfully wrote input to \"{}\".", &input_path);
     println!("🎄 Successfully wrote puzzle to \"{}\".", &puzzle_path);
+    Ok(Download::Downloaded(output))
+}
+
+/// Downloads only the puzzle of `day`, e.g. to get the description of part two once part one is
+/// solved.
+pub fn download_puzzle(day: Day, year: Option<u16>) -> Result<Output, AocCommandError> {
+    let puzzle_path = get_puzzle_path(day);
+
+    let args = build_args(
+        "download",
+        &[
+            "--overwrite".into(),
+            "--puzzle-only".into(),
+            "--puzzle-file".into(),
+            puzzle_path.to_string(),
+        ],
+        day,
+        year,
+    );
+
+    let output = call_aoc_cli(&args)?;
+    println!("🎄 Successfully wrote puzzle to \"{}\".", &puzzle_path);
     Ok(output)
 }
 
-pub fn submit(day: Day, part: u8, result: &str) -> Result<Output, AocCommandError> {
+pub fn submit(day: Day, part: u8, result: &str) -> Result<SubmissionOutcome, AocCommandError> {
     // workaround: the argument order is inverted for submit.
-    let mut args = build_args("submit", &[], day);
+    let mut args = build_args("submit", &[], day, config::get().year);
     args.push(part.to_string());
     args.push(result.to_string());
-    call_aoc_cli(&args)
+    let output = call_aoc_cli_with(&args, Stdio::piped())?;
+    let response = String::from_utf8_lossy(&output.stdout);
+    parse_submission(&response)
+        .ok_or_else(|| AocCommandError::UnexpectedResponse(response.trim().to_string()))
 }
 
 fn get_input_path(day: Day) -> String {
-    format!("data/inputs/{day}.txt")
+    config::get().paths.input(day).display().to_string()
 }
 
 fn get_puzzle_path(day: Day) -> String {
-    format!("data/puzzles/{day}.md")
-}
-
-fn get_year() -> Option<u16> {
-    match std::env::var("AOC_YEAR") {
-        Ok(x) => x.parse().ok().or(None),
-        Err(_) => None,
-    }
+    config::get().paths.puzzle(day).display().to_string()
 }
 
-fn build_args(command: &str, args: &[String], day: Day) -> Vec<String> {
+fn build_args(command: &str, args: &[String], day: Day, year: Option<u16>) -> Vec<String> {
     let mut cmd_args = args.to_vec();
 
-    if let Some(year) = get_year() {
+    if let Some(year) = year {
         cmd_args.push("--year".into());
         cmd_args.push(year.to_string());
     }
@@ -108,11 +249,47 @@ fn build_args(command: &str, args: &[String], day: Day) -> Vec<String> {
     cmd_args
 }
 
+/// How long to wait before the next request, given the time of the last one.
+fn throttle_delay(last_request: Option<SystemTime>, now: SystemTime) -> Duration {
+    last_request
+        .and_then(|last| now.duration_since(last).ok())
+        .map_or(Duration::ZERO, |elapsed| {
+            MIN_REQUEST_INTERVAL.saturating_sub(elapsed)
+        })
+}
+
+/// Waits until [`MIN_REQUEST_INTERVAL`] has passed since the last request recorded in
+/// `paths.last_request`, then records a new one.
+fn throttle() {
+    let path = &config::get().paths.last_request;
+    let last_request = fs::read_to_string(path)
+        .ok()
+        .and_then(|millis| millis.trim().parse().ok())
+        .map(|millis| UNIX_EPOCH + Duration::from_millis(millis));
+    let delay = throttle_delay(last_request, SystemTime::now());
+    if !delay.is_zero() {
+        println!(
+            "Waiting {:.1}s before the next request to adventofcode.com...",
+            delay.as_secs_f64()
+        );
+        thread::sleep(delay);
+    }
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+    if let Err(e) = fs::write(path, now.as_millis().to_string()) {
+        eprintln!("Could not write \"{}\": {e}", path.display());
+    }
+}
+
 fn call_aoc_cli(args: &[String]) -> Result<Output, AocCommandError> {
+    call_aoc_cli_with(args, Stdio::inherit())
+}
+
+fn call_aoc_cli_with(args: &[String], stdout: Stdio) -> Result<Output, AocCommandError> {
     // println!("Calling >aoc with: {}", args.join(" "));
+    throttle();
     let output = Command::new("aoc")
         .args(args)
-        .stdout(Stdio::inherit())
+        .stdout(stdout)
         .stderr(Stdio::inherit())
         .output()
         .map_err(|_| AocCommandError::CommandNotCallable)?;
@@ -123,3 +300,48 @@ fn call_aoc_cli(args: &[String]) -> Result<Output, AocCommandError> {
         Err(AocCommandError::BadExitStatus(output))
     }
 }
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn throttles_requests() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_000);
+        assert_eq!(throttle_delay(None, now), Duration::ZERO);
+        assert_eq!(
+            throttle_delay(Some(now - Duration::from_secs(2)), now),
+            MIN_REQUEST_INTERVAL - Duration::from_secs(2)
+        );
+        assert_eq!(
+            throttle_delay(Some(now - MIN_REQUEST_INTERVAL * 2), now),
+            Duration::ZERO
+        );
+        // a timestamp from the future, e.g. after the clock changed, doesn't block.
+        assert_eq!(
+            throttle_delay(Some(now + Duration::from_secs(60)), now),
+            Duration::ZERO
+        );
+    }
+
+    #[test]
+    fn parses_submissions() {
+        let cases = [
+            ("That's the right answer! You are one gold star closer to saving your vacation.", SubmissionOutcome::Correct),
+            ("That's not the right answer; your answer is too high. If you're stuck, make sure\nyou're using the full input data.", SubmissionOutcome::TooHigh),
+            ("\x1b[31mThat's not the right answer; your answer is too\nlow.\x1b[0m", SubmissionOutcome::TooLow),
+            ("That's not the right answer. Please wait one minute before trying again.", SubmissionOutcome::Wrong),
+            ("You gave an answer too recently; you have to wait after submitting an answer before trying again. You have 4m 32s left to wait.", SubmissionOutcome::RateLimited(Duration::from_secs(272))),
+            ("You gave an answer too recently. You have 9s left to wait.", SubmissionOutcome::RateLimited(Duration::from_secs(9))),
+            ("You don't seem to be solving the right level. Did you already complete it?", SubmissionOutcome::AlreadySolved),
+        ];
+        for (response, outcome) in cases {
+            assert_eq!(parse_submission(response), Some(outcome), "{response}");
+        }
+        assert_eq!(parse_submission("Internal Server Error"), None);
+        assert_eq!(
+            parse_submission("You gave an answer too recently. You have soon left to wait."),
+            None
+        );
+    }
+}
@@ -1,14 +1,16 @@
-use crate::template::{aoc_cli, Day};
+use crate::template::{aoc_cli, config, sealed, Day};
 use std::process;
 
-pub fn handle(day: Day) {
+pub fn handle(day: Day, force: bool) {
     if aoc_cli::check().is_err() {
         eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
         process::exit(1);
     }
 
-    if let Err(e) = aoc_cli::download(day) {
+    if let Err(e) = aoc_cli::download(day, config::get().year, force) {
         eprintln!("failed to call aoc-cli: {e}");
         process::exit(1);
     };
+
+    sealed::warn_if_tracked();
 }
@@ -0,0 +1,169 @@
+use std::path::Path;
+use std::str::FromStr;
+use std::{env, fs, process};
+
+use crate::template::config;
+use crate::template::sealed;
+
+/// Used instead of prompting for the passphrase if set, e.g. in CI.
+const PASSPHRASE_VAR: &str = "AOC_INPUTS_PASSPHRASE";
+
+pub enum Action {
+    Seal,
+    Unseal,
+    Check,
+}
+
+impl FromStr for Action {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "seal" => Ok(Action::Seal),
+            "unseal" => Ok(Action::Unseal),
+            "check" => Ok(Action::Check),
+            _ => Err(format!(
+                "unknown action `{s}`, expected `seal`, `unseal` or `check`."
+            )),
+        }
+    }
+}
+
+fn fail(message: impl std::fmt::Display) -> ! {
+    eprintln!("{message}");
+    process::exit(1);
+}
+
+/// Reads the passphrase from the key file, the environment or the terminal, in that order.
+/// `confirm` asks for a new passphrase twice.
+fn passphrase(key_file: Option<&Path>, confirm: bool) -> Vec<u8> {
+    let passphrase = if let Some(key_file) = key_file {
+        let key = fs::read(key_file)
+            .unwrap_or_else(|e| fail(format!("Could not read \"{}\": {e}", key_file.display())));
+        // editors add a trailing newline to the key file.
+        let key = key.strip_suffix(b"\n").unwrap_or(&key);
+        key.strip_suffix(b"\r").unwrap_or(key).to_vec()
+    } else if let Ok(passphrase) = env::var(PASSPHRASE_VAR) {
+        passphrase.into_bytes()
+    } else {
+        let prompt = |prompt: &str| {
+            rpassword::prompt_password(prompt)
+                .unwrap_or_else(|e| fail(format!("Could not read the passphrase: {e}")))
+        };
+        let passphrase = prompt("Passphrase: ");
+        if confirm && prompt("Repeat passphrase: ") != passphrase {
+            fail("The passphrases don't match.");
+        }
+        passphrase.into_bytes()
+    };
+    if passphrase.is_empty() {
+        fail("The passphrase must not be empty.");
+    }
+    passphrase
+}
+
+/// Adds the local files to the archive, replacing the files with the same name. Files that only
+/// exist in the archive are kept, so sealing on a machine with some of the inputs loses nothing.
+fn seal(key_file: Option<&Path>, force: bool) {
+    let archive_path = &config::get().paths.sealed_inputs;
+    let mut entries =
+        sealed::collect().unwrap_or_else(|e| fail(format!("Could not read the inputs: {e}")));
+    if entries.is_empty() {
+        fail("There are no inputs or puzzles to seal.");
+    }
+    let archive = fs::read(archive_path).ok();
+    let passphrase = passphrase(key_file, archive.is_none());
+
+    if let Some(archive) = archive {
+        match sealed::unseal(&archive, &passphrase) {
+            Ok(sealed) => {
+                let mut merged = sealed.clone();
+                merged.retain(|s| {
+                    !entries
+                        .iter()
+                        .any(|e| e.folder == s.folder && e.name == s.name)
+                });
+                merged.append(&mut entries);
+                merged.sort();
+                if merged == sealed {
+                    println!("\"{}\" is up to date.", archive_path.display());
+                    sealed::warn_if_tracked();
+                    return;
+                }
+                entries = merged;
+            }
+            Err(e) if !force => fail(format!(
+                "\"{}\": {e} Use `--force` to replace it with a new archive.",
+                archive_path.display()
+            )),
+            Err(_) => {}
+        }
+    }
+
+    let archive = sealed::seal(&entries, &passphrase)
+        .unwrap_or_else(|e| fail(format!("Could not seal the inputs: {e}")));
+    fs::write(archive_path, archive).unwrap_or_else(|e| {
+        fail(format!(
+            "Could not write \"{}\": {e}",
+            archive_path.display()
+        ))
+    });
+    println!(
+        "🔒 Sealed {} files into \"{}\".",
+        entries.len(),
+        archive_path.display()
+    );
+    sealed::warn_if_tracked();
+}
+
+/// Writes the files of the archive. Existing files with other contents are kept unless `force`
+/// is set.
+fn unseal(key_file: Option<&Path>, force: bool) {
+    let archive_path = &config::get().paths.sealed_inputs;
+    let archive = fs::read(archive_path).unwrap_or_else(|e| {
+        fail(format!(
+            "Could not read \"{}\": {e}. Run `cargo inputs seal` first.",
+            archive_path.display()
+        ))
+    });
+    let entries = sealed::unseal(&archive, &passphrase(key_file, false))
+        .unwrap_or_else(|e| fail(format!("\"{}\": {e}", archive_path.display())));
+
+    let (mut written, mut unchanged, mut kept) = (0, 0, vec![]);
+    for entry in &entries {
+        let path = entry.path();
+        match fs::read(&path) {
+            Ok(existing) if existing == entry.contents => unchanged += 1,
+            Ok(existing) if !existing.is_empty() && !force => kept.push(path),
+            _ => {
+                fs::write(&path, &entry.contents).unwrap_or_else(|e| {
+                    fail(format!("Could not write \"{}\": {e}", path.display()))
+                });
+                written += 1;
+            }
+        }
+    }
+
+    println!("🔓 Unsealed {written} files, {unchanged} were up to date.");
+    if !kept.is_empty() {
+        println!("Kept {} files that differ from the archive:", kept.len());
+        for path in kept {
+            println!("  {}", path.display());
+        }
+        println!("Use `--force` to overwrite them.");
+    }
+    sealed::warn_if_tracked();
+}
+
+pub fn handle(action: Action, key_file: Option<&Path>, force: bool) {
+    match action {
+        Action::Seal => seal(key_file, force),
+        Action::Unseal => unseal(key_file, force),
+        Action::Check => {
+            if sealed::warn_if_tracked() {
+                process::exit(1);
+            }
+            println!("No plaintext inputs are tracked by git.");
+        }
+    }
+}
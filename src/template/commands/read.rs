@@ -1,6 +1,6 @@
 use std::process;
 
-use crate::template::{aoc_cli, Day};
+use crate::template::{aoc_cli, config, Day};
 
 pub fn handle(day: Day) {
     if aoc_cli::check().is_err() {
@@ -8,7 +8,7 @@ pub fn handle(day: Day) {
         process::exit(1);
     }
 
-    if let Err(e) = aoc_cli::read(day) {
+    if let Err(e) = aoc_cli::read(day, config::get().year) {
         eprintln!("failed to call aoc-cli: {e}");
         process::exit(1);
     };
@@ -2,7 +2,14 @@ use std::process::{Command, Stdio};
 
 use crate::template::Day;
 
-pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
+/// The arguments for `cargo` that run the solution for `day`.
+pub fn cargo_args(
+    day: Day,
+    release: bool,
+    dhat: bool,
+    submit_part: Option<u8>,
+    args: &[String],
+) -> Vec<String> {
     let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), day.to_string()];
 
     if dhat {
@@ -23,6 +30,13 @@ pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
         cmd_args.push(submit_part.to_string());
     }
 
+    cmd_args.extend_from_slice(args);
+    cmd_args
+}
+
+pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>, args: &[String]) {
+    let cmd_args = cargo_args(day, release, dhat, submit_part, args);
+
     let mut cmd = Command::new("cargo")
         .args(&cmd_args)
         .stdout(Stdio::inherit())
@@ -0,0 +1,249 @@
+use std::fs;
+use std::io::{stderr, Write};
+use std::process::{Command, Stdio};
+
+use crate::template::answers::Answers;
+use crate::template::run_multi::child_commands::parse_results;
+use crate::template::timings::Timings;
+use crate::template::{all_days, config, Day, ANSI_BOLD, ANSI_RESET};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum FileStatus {
+    Missing,
+    Empty,
+    Present,
+}
+
+impl FileStatus {
+    fn of(path: &std::path::Path) -> Self {
+        match fs::metadata(path) {
+            Ok(metadata) if metadata.len() > 0 => FileStatus::Present,
+            Ok(_) => FileStatus::Empty,
+            Err(_) => FileStatus::Missing,
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            FileStatus::Missing => "✖",
+            FileStatus::Empty => "empty",
+            FileStatus::Present => "✔",
+        }
+    }
+}
+
+/// The outcome of running a day's solution on its real input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Run {
+    Skipped,
+    Failed,
+    Done([Option<String>; 2]),
+}
+
+struct DayStatus {
+    day: Day,
+    bin: bool,
+    input: FileStatus,
+    example: FileStatus,
+    run: Run,
+    tests: Option<bool>,
+    answers: [Option<String>; 2],
+    benchmark: Option<f64>,
+}
+
+impl DayStatus {
+    fn none_parts(&self) -> String {
+        match &self.run {
+            Run::Skipped => "?".into(),
+            Run::Failed => "panic".into(),
+            Run::Done(results) => {
+                let parts = (1..=2)
+                    .filter(|part| results[part - 1].is_none())
+                    .map(|part| part.to_string())
+                    .collect::<Vec<_>>();
+                if parts.is_empty() {
+                    "-".into()
+                } else {
+                    parts.join(" ")
+                }
+            }
+        }
+    }
+
+    /// `✔` if a part's result matches its known answer, `✘` if it differs, `?` if either is
+    /// unknown.
+    fn answer_status(&self) -> String {
+        (0..2)
+            .map(|i| {
+                let Run::Done(results) = &self.run else {
+                    return "?";
+                };
+                match (&results[i], &self.answers[i]) {
+                    (Some(result), Some(answer)) if result == answer => "✔",
+                    (_, Some(_)) => "✘",
+                    _ => "?",
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn columns(&self) -> Vec<String> {
+        let yes_no = |b: bool| if b { "✔" } else { "✖" };
+        vec![
+            self.day.to_string(),
+            yes_no(self.bin).into(),
+            self.input.symbol().into(),
+            self.example.symbol().into(),
+            self.none_parts(),
+            match self.tests {
+                Some(true) => "pass".into(),
+                Some(false) => "fail".into(),
+                None => "?".into(),
+            },
+            self.answer_status(),
+            self.benchmark.map_or("-".into(), |nanos| {
+                format!("{:.2}ms", nanos / 1_000_000_f64)
+            }),
+        ]
+    }
+}
+
+const HEADERS: [&str; 8] = [
+    "Day",
+    "Bin",
+    "Input",
+    "Example",
+    "None",
+    "Tests",
+    "Answers",
+    "Benchmark",
+];
+
+fn format_table(statuses: &[DayStatus]) -> String {
+    let rows = statuses.iter().map(DayStatus::columns).collect::<Vec<_>>();
+    let widths = HEADERS
+        .iter()
+        .enumerate()
+        .map(|(i, header)| {
+            rows.iter()
+                .map(|row| row[i].chars().count())
+                .chain([header.len()])
+                .max()
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+    let format_row = |row: Vec<String>| {
+        row.iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+    let header = format_row(HEADERS.iter().map(|h| h.to_string()).collect());
+    let mut lines = vec![format!("{ANSI_BOLD}{header}{ANSI_RESET}")];
+    lines.extend(rows.into_iter().map(format_row));
+    lines.join("\n")
+}
+
+fn cargo(args: &[&str]) -> Option<String> {
+    let output = Command::new("cargo")
+        .args(args)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn check_day(day: Day, quick: bool, answers: &Answers, timings: &Timings) -> DayStatus {
+    let paths = &config::get().paths;
+    let bin = paths.bin(day).exists();
+    let input = FileStatus::of(&paths.input(day));
+    let day_str = day.to_string();
+
+    let run = if quick || !bin || input != FileStatus::Present {
+        Run::Skipped
+    } else {
+        match cargo(&["run", "--quiet", "--release", "--bin", &day_str]) {
+            Some(output) => Run::Done(parse_results(&output)),
+            None => Run::Failed,
+        }
+    };
+    let tests = (!quick && bin).then(|| cargo(&["test", "--quiet", "--bin", &day_str]).is_some());
+
+    DayStatus {
+        day,
+        bin,
+        input,
+        example: FileStatus::of(&paths.example(day)),
+        run,
+        tests,
+        answers: [1, 2].map(|part| answers.get(day, part).map(String::from)),
+        benchmark: timings
+            .data
+            .iter()
+            .find(|t| t.day == day)
+            .map(|t| t.total_nanos),
+    }
+}
+
+pub fn handle(quick: bool) {
+    let answers = Answers::read_from_file();
+    let timings = Timings::read_from_file();
+    let statuses = all_days()
+        .map(|day| {
+            eprint!("\rChecking day {day}...");
+            let _ = stderr().flush();
+            check_day(day, quick, &answers, &timings)
+        })
+        .collect::<Vec<_>>();
+    eprint!("\r                    \r");
+    println!("{}", format_table(&statuses));
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+    use crate::day;
+
+    #[test]
+    fn formats_table() {
+        let statuses = [
+            DayStatus {
+                day: day!(1),
+                bin: true,
+                input: FileStatus::Present,
+                example: FileStatus::Present,
+                run: Run::Done([Some("11".into()), None]),
+                tests: Some(true),
+                answers: [Some("11".into()), Some("31".into())],
+                benchmark: Some(1_234_567.0),
+            },
+            DayStatus {
+                day: day!(2),
+                bin: false,
+                input: FileStatus::Empty,
+                example: FileStatus::Missing,
+                run: Run::Skipped,
+                tests: None,
+                answers: [None, None],
+                benchmark: None,
+            },
+        ];
+        let table = format_table(&statuses);
+        let lines = table.lines().skip(1).collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            vec![
+                "01   ✔    ✔      ✔        2     pass   ✔ ✘      1.23ms",
+                "02   ✖    empty  ✖        ?     ?      ? ?      -",
+            ]
+        );
+    }
+}
@@ -0,0 +1,8 @@
+use crate::template::commands::solve;
+use crate::template::Day;
+
+pub fn handle(day: Day, args: &[String]) {
+    let mut args = args.to_vec();
+    args.insert(0, "--stress".to_string());
+    solve::handle(day, true, false, None, &args);
+}
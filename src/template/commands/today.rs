@@ -0,0 +1,426 @@
+use std::fmt::Display;
+use std::io::{stdout, Write};
+use std::process;
+use std::time::Duration;
+
+use chrono::{DateTime, Datelike, FixedOffset, TimeDelta, TimeZone, Utc};
+
+use crate::template::commands::scaffold;
+use crate::template::{aoc_cli, config, Day};
+
+/// A puzzle that unlocked this long ago still counts as the next one for `--wait`, so waiting
+/// shortly after midnight doesn't skip it.
+const UNLOCK_GRACE: TimeDelta = TimeDelta::minutes(10);
+/// Waits a moment past midnight, in case the local clock is slightly ahead of the server's.
+const UNLOCK_MARGIN: TimeDelta = TimeDelta::seconds(2);
+/// The input of a puzzle that just unlocked is sometimes not ready for a few seconds.
+const DOWNLOAD_ATTEMPTS: u32 = 5;
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// The source of the current time, so the scheduling can be tested without waiting.
+pub trait Clock {
+    fn now(&self) -> DateTime<Utc>;
+    fn sleep(&self, duration: Duration);
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        std::thread::sleep(duration);
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum TodayError {
+    NoEvent(i32),
+    NotStarted(i32),
+    AllScaffolded(i32),
+}
+
+impl Display for TodayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TodayError::NoEvent(year) => write!(f, "There is no Advent of Code {year}."),
+            TodayError::NotStarted(year) => write!(
+                f,
+                "Advent of Code {year} hasn't started yet. Use `--wait` to wait for the first \
+                puzzle or `scaffold` with a specific day."
+            ),
+            TodayError::AllScaffolded(year) => {
+                write!(f, "All puzzles of Advent of Code {year} are scaffolded.")
+            }
+        }
+    }
+}
+
+/// The puzzle to fetch, and when it unlocks if that is in the future.
+#[derive(Debug, PartialEq, Eq)]
+struct Plan {
+    day: Day,
+    unlock: Option<DateTime<Utc>>,
+}
+
+/// The number of puzzles of an event. Since 2025, there are 12 instead of 25.
+fn puzzle_count(year: i32) -> u8 {
+    if year >= 2025 {
+        12
+    } else {
+        25
+    }
+}
+
+fn unlock_time(year: i32, day: u8, offset: FixedOffset) -> DateTime<Utc> {
+    offset
+        .with_ymd_and_hms(year, 12, u32::from(day), 0, 0, 0)
+        .unwrap()
+        .with_timezone(&Utc)
+}
+
+/// The number of puzzles of `year` that have unlocked at `now`.
+fn unlocked_days(year: i32, now: DateTime<Utc>, offset: FixedOffset) -> u8 {
+    (1..=puzzle_count(year))
+        .rev()
+        .find(|day| unlock_time(year, *day, offset) <= now)
+        .unwrap_or(0)
+}
+
+/// Picks the puzzle of `year` to fetch:
+///  - the first one that isn't `scaffolded` yet if the event is over,
+///  - otherwise the next one to unlock if `wait` is set,
+///  - otherwise the one that unlocked today.
+fn plan(
+    now: DateTime<Utc>,
+    offset: FixedOffset,
+    year: i32,
+    wait: bool,
+    scaffolded: impl Fn(Day) -> bool,
+) -> Result<Plan, TodayError> {
+    if year < 2015 {
+        return Err(TodayError::NoEvent(year));
+    }
+    let count = puzzle_count(year);
+    let unlocked = unlocked_days(year, now, offset);
+    if unlocked == count {
+        let day = (1..=count)
+            .filter_map(Day::new)
+            .find(|day| !scaffolded(*day))
+            .ok_or(TodayError::AllScaffolded(year))?;
+        return Ok(Plan { day, unlock: None });
+    }
+    if !wait {
+        let day = Day::new(unlocked).ok_or(TodayError::NotStarted(year))?;
+        return Ok(Plan { day, unlock: None });
+    }
+    let day = match unlocked_days(year, now - UNLOCK_GRACE, offset) {
+        just_unlocked if just_unlocked < unlocked => unlocked,
+        _ => unlocked + 1,
+    };
+    let unlock = unlock_time(year, day, offset);
+    Ok(Plan {
+        day: Day::new(day).unwrap(),
+        unlock: (unlock > now).then_some(unlock),
+    })
+}
+
+fn format_countdown(remaining: TimeDelta) -> String {
+    let seconds = remaining.num_seconds().max(0);
+    let (days, seconds) = (seconds / 86_400, seconds % 86_400);
+    let time = format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    );
+    if days > 0 {
+        format!("{days}d {time}")
+    } else {
+        time
+    }
+}
+
+/// Shows a countdown until `unlock` and returns once it has passed.
+fn wait_until(clock: &impl Clock, day: Day, unlock: DateTime<Utc>) {
+    let target = unlock + UNLOCK_MARGIN;
+    loop {
+        let remaining = target - clock.now();
+        if remaining <= TimeDelta::zero() {
+            break;
+        }
+        print!(
+            "\r⏳ Day {day} unlocks in {}  ",
+            format_countdown(remaining + TimeDelta::milliseconds(999))
+        );
+        let _ = stdout().flush();
+        // wake up when the displayed second changes.
+        let step = match remaining.num_milliseconds() % 1000 {
+            0 => remaining.num_milliseconds().clamp(1, 1000),
+            ms => ms,
+        };
+        clock.sleep(Duration::from_millis(step as u64));
+    }
+    println!("\r🔓 Day {day} has unlocked!{:20}", "");
+}
+
+/// Calls `f` up to `attempts` times, sleeping `delay` between failed attempts.
+fn retry<T, E: Display>(
+    clock: &impl Clock,
+    attempts: u32,
+    delay: Duration,
+    mut f: impl FnMut() -> Result<T, E>,
+) -> Result<T, E> {
+    let mut attempt = 1;
+    loop {
+        match f() {
+            Err(e) if attempt < attempts => {
+                eprintln!(
+                    "Attempt {attempt} of {attempts} failed: {e} Retrying in {}s...",
+                    delay.as_secs()
+                );
+                clock.sleep(delay);
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+fn run(clock: &impl Clock, wait: bool, year: Option<u16>) -> Result<(), String> {
+    let config = config::get();
+    let offset = FixedOffset::east_opt(config.server_utc_offset * 3600).unwrap();
+    let now = clock.now();
+    let year = year
+        .or(config.year)
+        .map_or_else(|| now.with_timezone(&offset).year(), i32::from);
+    let plan = plan(now, offset, year, wait, |day| {
+        config.paths.bin(day).exists()
+    })
+    .map_err(|e| e.to_string())?;
+
+    if aoc_cli::check().is_err() {
+        return Err("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.".into());
+    }
+
+    if let Some(unlock) = plan.unlock {
+        wait_until(clock, plan.day, unlock);
+    }
+
+    let day = plan.day;
+    let year = Some(year as u16);
+    if config.paths.bin(day).exists() {
+        println!("Module file for day {day} already exists, skipping scaffold.");
+    } else {
+        scaffold::handle(day, false);
+    }
+    retry(clock, DOWNLOAD_ATTEMPTS, RETRY_DELAY, || {
+        aoc_cli::download(day, year, false)
+    })
+    .map_err(|e| format!("failed to call aoc-cli: {e}"))?;
+    aoc_cli::read(day, year).map_err(|e| format!("failed to call aoc-cli: {e}"))?;
+    Ok(())
+}
+
+/// Entry point for `cargo today [--wait] [--year <year>]`.
+pub fn handle(wait: bool, year: Option<u16>) {
+    if let Err(e) = run(&SystemClock, wait, year) {
+        eprintln!("{e}");
+        process::exit(1);
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+    use crate::day;
+    use std::cell::{Cell, RefCell};
+
+    /// A clock that only moves when it sleeps.
+    struct FakeClock {
+        now: Cell<DateTime<Utc>>,
+        sleeps: RefCell<Vec<Duration>>,
+    }
+
+    impl FakeClock {
+        fn at(now: DateTime<Utc>) -> Self {
+            Self {
+                now: Cell::new(now),
+                sleeps: RefCell::new(vec![]),
+            }
+        }
+    }
+
+    impl Clock for FakeClock {
+        fn now(&self) -> DateTime<Utc> {
+            self.now.get()
+        }
+
+        fn sleep(&self, duration: Duration) {
+            self.now
+                .set(self.now.get() + TimeDelta::from_std(duration).unwrap());
+            self.sleeps.borrow_mut().push(duration);
+        }
+    }
+
+    fn server() -> FixedOffset {
+        FixedOffset::west_opt(5 * 3600).unwrap()
+    }
+
+    /// A time in the server's time zone.
+    fn at(year: i32, month: u32, day: u32, h: u32, m: u32) -> DateTime<Utc> {
+        server()
+            .with_ymd_and_hms(year, month, day, h, m, 0)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn nothing_scaffolded(_: Day) -> bool {
+        false
+    }
+
+    #[test]
+    fn plans_today() {
+        let result = plan(
+            at(2024, 12, 5, 9, 0),
+            server(),
+            2024,
+            false,
+            nothing_scaffolded,
+        );
+        assert_eq!(
+            result,
+            Ok(Plan {
+                day: day!(5),
+                unlock: None
+            })
+        );
+        let result = plan(
+            at(2024, 11, 30, 23, 59),
+            server(),
+            2024,
+            false,
+            nothing_scaffolded,
+        );
+        assert_eq!(result, Err(TodayError::NotStarted(2024)));
+        let result = plan(
+            at(2024, 12, 5, 9, 0),
+            server(),
+            2014,
+            false,
+            nothing_scaffolded,
+        );
+        assert_eq!(result, Err(TodayError::NoEvent(2014)));
+    }
+
+    #[test]
+    fn plans_next_unlock() {
+        let result = plan(
+            at(2024, 12, 4, 23, 58),
+            server(),
+            2024,
+            true,
+            nothing_scaffolded,
+        );
+        assert_eq!(
+            result,
+            Ok(Plan {
+                day: day!(5),
+                unlock: Some(at(2024, 12, 5, 0, 0))
+            })
+        );
+        // the puzzle that unlocked a few minutes ago is fetched right away.
+        let result = plan(
+            at(2024, 12, 5, 0, 3),
+            server(),
+            2024,
+            true,
+            nothing_scaffolded,
+        );
+        assert_eq!(
+            result,
+            Ok(Plan {
+                day: day!(5),
+                unlock: None
+            })
+        );
+        let result = plan(
+            at(2024, 12, 5, 0, 30),
+            server(),
+            2024,
+            true,
+            nothing_scaffolded,
+        );
+        assert_eq!(result.unwrap().day, day!(6));
+        let result = plan(
+            at(2026, 10, 19, 12, 0),
+            server(),
+            2026,
+            true,
+            nothing_scaffolded,
+        );
+        assert_eq!(
+            result,
+            Ok(Plan {
+                day: day!(1),
+                unlock: Some(at(2026, 12, 1, 0, 0))
+            })
+        );
+    }
+
+    #[test]
+    fn plans_catching_up() {
+        let scaffolded = |day: Day| day.into_inner() <= 3;
+        let result = plan(at(2026, 10, 19, 12, 0), server(), 2024, true, scaffolded);
+        assert_eq!(
+            result,
+            Ok(Plan {
+                day: day!(4),
+                unlock: None
+            })
+        );
+        // 2025 only had 12 puzzles.
+        let result = plan(at(2025, 12, 13, 0, 0), server(), 2025, false, |_| true);
+        assert_eq!(result, Err(TodayError::AllScaffolded(2025)));
+    }
+
+    #[test]
+    fn counts_down_to_unlock() {
+        let clock = FakeClock::at(at(2024, 12, 4, 23, 59) + TimeDelta::milliseconds(500));
+        wait_until(&clock, day!(5), at(2024, 12, 5, 0, 0));
+        assert!(clock.now() >= at(2024, 12, 5, 0, 0) + UNLOCK_MARGIN);
+        assert!(clock.now() < at(2024, 12, 5, 0, 0) + UNLOCK_MARGIN + TimeDelta::seconds(1));
+        assert!(clock
+            .sleeps
+            .borrow()
+            .iter()
+            .all(|d| *d <= Duration::from_secs(1)));
+
+        assert_eq!(format_countdown(TimeDelta::seconds(59)), "00:00:59");
+        assert_eq!(
+            format_countdown(TimeDelta::seconds(86_400 + 3723)),
+            "1d 01:02:03"
+        );
+    }
+
+    #[test]
+    fn retries_downloads() {
+        let clock = FakeClock::at(at(2024, 12, 5, 0, 0));
+        let mut calls = 0;
+        let result = retry(&clock, 5, RETRY_DELAY, || {
+            calls += 1;
+            if calls < 3 {
+                Err("not ready.")
+            } else {
+                Ok(calls)
+            }
+        });
+        assert_eq!(result, Ok(3));
+        assert_eq!(clock.sleeps.borrow().len(), 2);
+
+        let result: Result<(), _> = retry(&clock, 2, RETRY_DELAY, || Err("not ready."));
+        assert_eq!(result, Err("not ready."));
+    }
+}
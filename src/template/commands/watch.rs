@@ -0,0 +1,198 @@
+use std::io::{stdout, BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant, SystemTime};
+use std::{fs, thread};
+
+use crate::template::commands::solve;
+use crate::template::run_multi::child_commands::parse_results;
+use crate::template::{config, Day, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+/// Changes are only acted upon once the files stayed the same for this long, so an editor
+/// saving several files doesn't trigger several runs.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+/// The modification time and length of every watched file, `None` if it doesn't exist.
+type Snapshot = Vec<Option<(SystemTime, u64)>>;
+
+/// The day's binary, `src/lib.rs`, and the day's input and examples (including the extra
+/// examples like `05-2.txt`).
+fn watched_files(day: Day) -> Vec<PathBuf> {
+    let paths = &config::get().paths;
+    let mut files = vec![
+        paths.bin(day),
+        "src/lib.rs".into(),
+        paths.input(day),
+        paths.example(day),
+    ];
+    let prefix = format!("{day}-");
+    if let Ok(entries) = fs::read_dir(&paths.examples) {
+        let mut examples = entries
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with(&prefix) && name.ends_with(".txt"))
+            })
+            .collect::<Vec<_>>();
+        examples.sort();
+        files.extend(examples);
+    }
+    files
+}
+
+fn snapshot(files: &[PathBuf]) -> Snapshot {
+    files
+        .iter()
+        .map(|path| {
+            let metadata = fs::metadata(path).ok()?;
+            Some((metadata.modified().ok()?, metadata.len()))
+        })
+        .collect()
+}
+
+/// Blocks until a watched file changes and then stops changing for [`DEBOUNCE`].
+fn wait_for_change(day: Day, previous: &Snapshot) -> Snapshot {
+    let mut current = snapshot(&watched_files(day));
+    while current == *previous {
+        thread::sleep(POLL_INTERVAL);
+        current = snapshot(&watched_files(day));
+    }
+    let mut changed_at = Instant::now();
+    while changed_at.elapsed() < DEBOUNCE {
+        thread::sleep(POLL_INTERVAL.min(DEBOUNCE));
+        let next = snapshot(&watched_files(day));
+        if next != current {
+            current = next;
+            changed_at = Instant::now();
+        }
+    }
+    current
+}
+
+/// Describes how the results of a run differ from the previous run.
+fn diff_results(previous: &[Option<String>; 2], current: &[Option<String>; 2]) -> Vec<String> {
+    let show = |result: &Option<String>| match result {
+        Some(result) if result.contains('\n') => "▼".to_string(),
+        Some(result) => result.clone(),
+        None => "✖".to_string(),
+    };
+    (0..2)
+        .map(|i| {
+            let part = i + 1;
+            if previous[i] == current[i] {
+                format!("Part {part}: unchanged")
+            } else {
+                format!(
+                    "Part {part}: {} → {ANSI_BOLD}{}{ANSI_RESET}",
+                    show(&previous[i]),
+                    show(&current[i])
+                )
+            }
+        })
+        .collect()
+}
+
+/// Runs cargo with `args`, printing its output as it arrives. Returns the output and whether
+/// the command succeeded.
+fn run_streamed(args: &[String]) -> (String, bool) {
+    let mut cmd = Command::new("cargo")
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .unwrap();
+
+    let mut output = String::new();
+    let stdout_pipe = BufReader::new(cmd.stdout.take().unwrap());
+    for line in stdout_pipe.lines() {
+        let line = line.unwrap();
+        println!("{line}");
+        output.push_str(&line);
+        output.push('\n');
+    }
+
+    (output, cmd.wait().is_ok_and(|status| status.success()))
+}
+
+/// Entry point for `cargo solve <day> --watch`. Re-runs the solution (or its tests if `tests` is
+/// set) whenever the day's files change, until interrupted.
+pub fn handle(day: Day, release: bool, tests: bool, args: &[String]) {
+    let cargo_args = if tests {
+        vec!["test".to_string(), "--bin".to_string(), day.to_string()]
+    } else {
+        solve::cargo_args(day, release, false, None, args)
+    };
+    let mut files = snapshot(&watched_files(day));
+    let mut previous: Option<[Option<String>; 2]> = None;
+    loop {
+        let watched = watched_files(day)
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect::<Vec<_>>();
+        print!("{CLEAR_SCREEN}");
+        println!(
+            "{ANSI_ITALIC}Watching {} (Ctrl-C to stop){ANSI_RESET}\n",
+            watched.join(", ")
+        );
+        let _ = stdout().flush();
+
+        let (output, success) = run_streamed(&cargo_args);
+
+        println!();
+        if tests {
+            let outcome = if success { "passed" } else { "failed" };
+            println!("{ANSI_BOLD}Tests {outcome}.{ANSI_RESET}");
+        } else if success {
+            let results = parse_results(&output);
+            if let Some(previous) = &previous {
+                println!("{ANSI_BOLD}Since the last run:{ANSI_RESET}");
+                for line in diff_results(previous, &results) {
+                    println!("  {line}");
+                }
+            }
+            previous = Some(results);
+        } else {
+            println!("{ANSI_BOLD}The solution failed.{ANSI_RESET}");
+        }
+
+        files = wait_for_change(day, &files);
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diffs_results() {
+        let previous = [Some("42".into()), None];
+        let current = [Some("42".into()), Some("#.\n.#".into())];
+        assert_eq!(
+            diff_results(&previous, &current),
+            vec![
+                "Part 1: unchanged".to_string(),
+                format!("Part 2: ✖ → {ANSI_BOLD}▼{ANSI_RESET}"),
+            ]
+        );
+    }
+
+    #[test]
+    fn snapshots_detect_changes() {
+        let path = std::env::temp_dir().join(format!("aoc-watch-{}.txt", std::process::id()));
+        let files = [path.clone()];
+        let missing = snapshot(&files);
+        assert_eq!(missing, vec![None]);
+
+        fs::write(&path, "a").unwrap();
+        let created = snapshot(&files);
+        assert_ne!(created, missing);
+
+        fs::write(&path, "ab").unwrap();
+        assert_ne!(snapshot(&files), created);
+        fs::remove_file(&path).unwrap();
+    }
+}
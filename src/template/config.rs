@@ -0,0 +1,298 @@
+/// Project settings, read from `aoc.toml` in the project root.
+///
+/// Every key is optional and defaults to the template's conventions, so the file can be left
+/// out entirely:
+///
+/// ```toml
+/// year = 2024
+///
+/// [paths]
+/// inputs = "data/inputs"
+/// examples = "data/examples"
+/// puzzles = "data/puzzles"
+/// timings = "data/timings.json"
+/// answers = "data/answers.json"
+/// last_request = "data/.last-request"
+/// sealed_inputs = "data/inputs.sealed"
+/// readme = "README.md"
+/// bins = "src/bin"
+///
+/// [server]
+/// utc_offset = -5
+/// ```
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::{fs, io};
+
+use toml::{Table, Value};
+
+use crate::template::Day;
+
+pub const CONFIG_FILE: &str = "aoc.toml";
+
+#[derive(Debug)]
+pub enum ConfigError {
+    IO(io::Error),
+    Parse(String),
+    UnknownKey(String),
+    InvalidValue { key: String, expected: &'static str },
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::IO(e) => write!(f, "{CONFIG_FILE}: {e}"),
+            ConfigError::Parse(e) => write!(f, "{CONFIG_FILE}: {e}"),
+            ConfigError::UnknownKey(key) => write!(f, "{CONFIG_FILE}: unknown key `{key}`"),
+            ConfigError::InvalidValue { key, expected } => {
+                write!(f, "{CONFIG_FILE}: `{key}` must be {expected}")
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Paths {
+    pub inputs: PathBuf,
+    pub examples: PathBuf,
+    pub puzzles: PathBuf,
+    pub timings: PathBuf,
+    /// The known correct answers, see [`crate::template::answers`].
+    pub answers: PathBuf,
+    /// The time of the last request to the Advent of Code server, used to throttle requests.
+    pub last_request: PathBuf,
+    /// The encrypted archive of the inputs and puzzles, see [`crate::template::sealed`].
+    pub sealed_inputs: PathBuf,
+    pub readme: PathBuf,
+    /// Note that cargo only discovers binaries in `src/bin` unless they are listed in
+    /// `Cargo.toml`.
+    pub bins: PathBuf,
+}
+
+impl Paths {
+    pub fn input(&self, day: Day) -> PathBuf {
+        self.inputs.join(format!("{day}.txt"))
+    }
+
+    pub fn example(&self, day: Day) -> PathBuf {
+        self.examples.join(format!("{day}.txt"))
+    }
+
+    pub fn puzzle(&self, day: Day) -> PathBuf {
+        self.puzzles.join(format!("{day}.md"))
+    }
+
+    pub fn bin(&self, day: Day) -> PathBuf {
+        self.bins.join(format!("{day}.rs"))
+    }
+
+    /// The folder for `read_file`, either `"inputs"`, `"examples"` or `"puzzles"`.
+    pub fn folder(&self, folder: &str) -> &Path {
+        match folder {
+            "inputs" => &self.inputs,
+            "examples" => &self.examples,
+            "puzzles" => &self.puzzles,
+            _ => panic!("unknown data folder `{folder}`"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Config {
+    /// The puzzle year passed to aoc-cli, which defaults to the latest event if this is `None`.
+    pub year: Option<u16>,
+    /// The UTC offset in hours of the Advent of Code server, which unlocks puzzles at midnight.
+    pub server_utc_offset: i32,
+    pub paths: Paths,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            year: None,
+            server_utc_offset: -5,
+            paths: Paths {
+                inputs: "data/inputs".into(),
+                examples: "data/examples".into(),
+                puzzles: "data/puzzles".into(),
+                timings: "data/timings.json".into(),
+                answers: "data/answers.json".into(),
+                last_request: "data/.last-request".into(),
+                sealed_inputs: "data/inputs.sealed".into(),
+                readme: "README.md".into(),
+                bins: "src/bin".into(),
+            },
+        }
+    }
+}
+
+/// Removes `name` from `table`, checking that it has the expected type.
+fn take<T>(
+    table: &mut Table,
+    prefix: &str,
+    name: &str,
+    expected: &'static str,
+    convert: impl FnOnce(Value) -> Option<T>,
+) -> Result<Option<T>, ConfigError> {
+    let key = format!("{prefix}{name}");
+    table
+        .remove(name)
+        .map(|value| convert(value).ok_or(ConfigError::InvalidValue { key, expected }))
+        .transpose()
+}
+
+fn integer(min: i64, max: i64) -> impl FnOnce(Value) -> Option<i64> {
+    move |value| value.as_integer().filter(|n| (min..=max).contains(n))
+}
+
+fn path(value: Value) -> Option<PathBuf> {
+    value.as_str().filter(|s| !s.is_empty()).map(PathBuf::from)
+}
+
+fn table(value: Value) -> Option<Table> {
+    match value {
+        Value::Table(table) => Some(table),
+        _ => None,
+    }
+}
+
+/// Fails on the first key that was not taken out of `table`.
+fn check_empty(table: &Table, prefix: &str) -> Result<(), ConfigError> {
+    match table.keys().next() {
+        Some(key) => Err(ConfigError::UnknownKey(format!("{prefix}{key}"))),
+        None => Ok(()),
+    }
+}
+
+impl Config {
+    pub fn parse(s: &str) -> Result<Self, ConfigError> {
+        let mut root: Table = s
+            .parse()
+            .map_err(|e: toml::de::Error| ConfigError::Parse(e.message().to_string()))?;
+        let mut config = Config::default();
+
+        if let Some(year) = take(
+            &mut root,
+            "",
+            "year",
+            "a year from 2015",
+            integer(2015, 9999),
+        )? {
+            config.year = Some(year as u16);
+        }
+
+        if let Some(mut server) = take(&mut root, "", "server", "a table", table)? {
+            let offset = take(
+                &mut server,
+                "server.",
+                "utc_offset",
+                "an integer from -12 to 14",
+                integer(-12, 14),
+            )?;
+            if let Some(offset) = offset {
+                config.server_utc_offset = offset as i32;
+            }
+            check_empty(&server, "server.")?;
+        }
+
+        if let Some(mut paths) = take(&mut root, "", "paths", "a table", table)? {
+            let fields = [
+                ("inputs", &mut config.paths.inputs),
+                ("examples", &mut config.paths.examples),
+                ("puzzles", &mut config.paths.puzzles),
+                ("timings", &mut config.paths.timings),
+                ("answers", &mut config.paths.answers),
+                ("last_request", &mut config.paths.last_request),
+                ("sealed_inputs", &mut config.paths.sealed_inputs),
+                ("readme", &mut config.paths.readme),
+                ("bins", &mut config.paths.bins),
+            ];
+            for (name, field) in fields {
+                if let Some(path) = take(&mut paths, "paths.", name, "a non-empty string", path)? {
+                    *field = path;
+                }
+            }
+            check_empty(&paths, "paths.")?;
+        }
+
+        check_empty(&root, "")?;
+        Ok(config)
+    }
+
+    /// Reads [`CONFIG_FILE`] from the current directory, or returns the defaults if it doesn't
+    /// exist.
+    pub fn load() -> Result<Self, ConfigError> {
+        match fs::read_to_string(CONFIG_FILE) {
+            Ok(s) => Config::parse(&s),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Config::default()),
+            Err(e) => Err(ConfigError::IO(e)),
+        }
+    }
+}
+
+/// The project's configuration, loaded once.
+///
+/// # Panics
+/// If the configuration file is invalid. Commands check it with [`Config::load`] first so they
+/// can report the error without a panic.
+pub fn get() -> &'static Config {
+    static CONFIG: OnceLock<Config> = OnceLock::new();
+    CONFIG.get_or_init(|| Config::load().unwrap_or_else(|e| panic!("{e}")))
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+
+    fn error(s: &str) -> String {
+        Config::parse(s).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn defaults() {
+        assert_eq!(Config::parse("").unwrap(), Config::default());
+        assert_eq!(
+            Config::default().paths.input(crate::day!(7)),
+            PathBuf::from("data/inputs/07.txt")
+        );
+    }
+
+    #[test]
+    fn overrides() {
+        let config =
+            Config::parse("year = 2022\n[paths]\ninputs = \"inputs\"\n[server]\nutc_offset = 1\n")
+                .unwrap();
+        assert_eq!(config.year, Some(2022));
+        assert_eq!(config.server_utc_offset, 1);
+        assert_eq!(config.paths.inputs, PathBuf::from("inputs"));
+        assert_eq!(config.paths.examples, PathBuf::from("data/examples"));
+    }
+
+    #[test]
+    fn errors_name_the_key() {
+        assert_eq!(
+            error("year = 1999"),
+            "aoc.toml: `year` must be a year from 2015"
+        );
+        assert_eq!(
+            error("year = \"2024\""),
+            "aoc.toml: `year` must be a year from 2015"
+        );
+        assert_eq!(
+            error("[server]\nutc_offset = 20"),
+            "aoc.toml: `server.utc_offset` must be an integer from -12 to 14"
+        );
+        assert_eq!(
+            error("[paths]\ninputs = 3"),
+            "aoc.toml: `paths.inputs` must be a non-empty string"
+        );
+        assert_eq!(error("paths = 3"), "aoc.toml: `paths` must be a table");
+        assert_eq!(
+            error("[paths]\ninput = \"x\""),
+            "aoc.toml: unknown key `paths.input`"
+        );
+        assert_eq!(error("years = 2024"), "aoc.toml: unknown key `years`");
+        assert!(error("year = ").starts_with("aoc.toml: "));
+    }
+}
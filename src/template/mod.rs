@@ -1,8 +1,14 @@
-use std::{env, fs};
+use std::fs;
 
+pub mod answers;
 pub mod aoc_cli;
 pub mod commands;
+pub mod config;
+pub mod params;
 pub mod runner;
+pub mod sealed;
+pub mod stress;
+pub mod variants;
 
 pub use day::*;
 
@@ -18,8 +24,10 @@ pub const ANSI_RESET: &str = "\x1b[0m";
 /// Helper function that reads a text file to a string.
 #[must_use]
 pub fn read_file(folder: &str, day: Day) -> String {
-    let cwd = env::current_dir().unwrap();
-    let filepath = cwd.join("data").join(folder).join(format!("{day}.txt"));
+    let filepath = config::get()
+        .paths
+        .folder(folder)
+        .join(format!("{day}.txt"));
     let f = fs::read_to_string(filepath);
     f.expect("could not open input file")
 }
@@ -27,10 +35,9 @@ pub fn read_file(folder: &str, day: Day) -> String {
 /// Helper function that reads a text file to string, appending a part suffix. E.g. like `01-2.txt`.
 #[must_use]
 pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
-    let cwd = env::current_dir().unwrap();
-    let filepath = cwd
-        .join("data")
-        .join(folder)
+    let filepath = config::get()
+        .paths
+        .folder(folder)
         .join(format!("{day}-{part}.txt"));
     let f = fs::read_to_string(filepath);
     f.expect("could not open input file")
@@ -39,19 +46,63 @@ pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
 /// Creates the constant `DAY` and sets up the input and runner for each part.
 ///
 /// The optional, second parameter (1 or 2) allows you to only run a single part of the solution.
+///
+/// Alternatively, the second parameter can map command-line flags to functions that take the
+/// input instead of running the parts, e.g. `solution!(17, modes: { "--debug" => debug })`.
+/// Flags are forwarded by `cargo solve 17 --debug`.
+///
+/// A `params: { size: i32 = 71 }` parameter declares named values with their defaults for the
+/// real input, which the parts read with `params().size`. Examples override them in a manifest
+/// and the command line with `--param size=7`, see [`params`].
+///
+/// A `generator: generate` parameter registers a [`stress::Generator`] for `cargo stress`, and
+/// adds a test that runs both parts on the inputs it saved.
+///
+/// A `variants: { 2 => part_two_brute_force }` parameter registers alternate implementations of
+/// a part. `cargo solve <day> --compare` and a generated test check that they agree with the
+/// part on every input.
+///
+/// The optional parameters have to be given in this order.
 #[macro_export]
 macro_rules! solution {
-    ($day:expr) => {
-        $crate::solution!(@impl $day, [part_one, 1] [part_two, 2]);
-    };
     ($day:expr, 1) => {
-        $crate::solution!(@impl $day, [part_one, 1]);
+        $crate::solution!(
+            @impl $day, modes: [], params: [], generator: [], variants: [], [part_one, 1]
+        );
     };
     ($day:expr, 2) => {
-        $crate::solution!(@impl $day, [part_two, 2]);
+        $crate::solution!(
+            @impl $day, modes: [], params: [], generator: [], variants: [], [part_two, 2]
+        );
+    };
+    (
+        $day:expr
+        $(, modes: { $( $flag:literal => $mode:expr ),+ $(,)? })?
+        $(, params: {
+            $( $(#[$param_meta:meta])* $param:ident : $param_ty:ty = $param_default:expr ),+ $(,)?
+        })?
+        $(, generator: $generator:expr)?
+        $(, variants: { $( $variant_part:literal => $variant:expr ),+ $(,)? })?
+        $(,)?
+    ) => {
+        $crate::solution!(
+            @impl $day,
+            modes: [$($( [$flag, $mode] )+)?],
+            params: [$($( [$(#[$param_meta])* $param : $param_ty = $param_default] )+)?],
+            generator: [$($generator)?],
+            variants: [$($( [$variant, $variant_part] )+)?],
+            [part_one, 1] [part_two, 2]
+        );
     };
 
-    (@impl $day:expr, $( [$func:expr, $part:expr] )*) => {
+    (
+        @impl $day:expr,
+        modes: [$( [$flag:literal, $mode:expr] )*],
+        params: [$( [$(#[$param_meta:meta])* $param:ident : $param_ty:ty = $param_default:expr] )*],
+        generator: [$($generator:expr)?],
+        variants: [$( [$variant:expr, $variant_part:expr] )*],
+        $( [$func:expr, $part:expr] )*
+    ) => {
         /// The current day.
         const DAY: $crate::template::Day = $crate::day!($day);
 
@@ -59,10 +110,115 @@ macro_rules! solution {
         #[global_allocator]
         static ALLOC: dhat::Alloc = dhat::Alloc;
 
+        /// The parameters of the solution, see [`params`].
+        #[allow(dead_code)]
+        #[derive(Clone, Debug)]
+        struct Params {
+            $( $(#[$param_meta])* $param: $param_ty, )*
+        }
+
+        impl Params {
+            /// The values for the real input with the current overrides applied.
+            #[allow(dead_code)]
+            fn get() -> Result<Self, String> {
+                #[allow(unused_mut)]
+                let mut params = Params { $( $param: $param_default, )* };
+                for (name, value) in $crate::template::params::overrides() {
+                    match name.as_str() {
+                        $(
+                            stringify!($param) => {
+                                params.$param = value.parse::<$param_ty>().map_err(|e| {
+                                    format!("invalid value `{value}` for parameter `{name}`: {e}")
+                                })?;
+                            }
+                        )*
+                        _ => return Err(format!("Day {DAY} has no parameter `{name}`")),
+                    }
+                }
+                Ok(params)
+            }
+        }
+
+        /// The parameters of the solution.
+        ///
+        /// # Panics
+        /// If an override is invalid. The binary checks the command line before running parts.
+        #[allow(dead_code)]
+        fn params() -> Params {
+            Params::get().unwrap_or_else(|e| panic!("{e}"))
+        }
+
+        #[allow(dead_code)]
+        const GENERATOR: Option<$crate::template::stress::Generator> =
+            $crate::solution!(@option [$($generator)?]);
+
+        #[allow(dead_code)]
+        fn solution_parts() -> Vec<$crate::template::stress::Part> {
+            vec![$( $crate::template::stress::Part::new($part, stringify!($func), $func) ),*]
+        }
+
+        #[allow(dead_code)]
+        fn solution_variants() -> Vec<$crate::template::stress::Part> {
+            vec![$(
+                $crate::template::stress::Part::new($variant_part, stringify!($variant), $variant)
+            ),*]
+        }
+
+        $crate::solution!(@test stress_regressions [$($generator)?] {
+            $crate::template::stress::check_regressions(DAY, &solution_parts());
+        });
+        $crate::solution!(@test variants_agree [$($variant)*] {
+            $crate::template::variants::check(
+                DAY,
+                &solution_parts(),
+                &solution_variants(),
+                GENERATOR,
+            );
+        });
+
         fn main() {
             use $crate::template::runner::*;
+            let overrides = $crate::template::params::from_args().and_then(|overrides| {
+                $crate::template::params::set_overrides(overrides);
+                Params::get()
+            });
+            if let Err(e) = overrides {
+                eprintln!("Error: {e}");
+                std::process::exit(1);
+            }
+            if std::env::args().any(|arg| arg == "--stress") {
+                let Some(generator) = GENERATOR else {
+                    eprintln!("Day {DAY} has no input generator.");
+                    std::process::exit(1);
+                };
+                return $crate::template::stress::run(DAY, generator, &solution_parts());
+            }
+            if std::env::args().any(|arg| arg == "--compare") {
+                return $crate::template::variants::run(
+                    DAY,
+                    &solution_parts(),
+                    &solution_variants(),
+                    GENERATOR,
+                );
+            }
             let input = $crate::template::read_file("inputs", DAY);
+            $(
+                if std::env::args().any(|arg| arg == $flag) {
+                    return $mode(&input);
+                }
+            )*
             $( run_part($func, &input, DAY, $part); )*
         }
     };
+
+    (@option []) => { None };
+    (@option [$value:expr]) => { Some($value) };
+
+    // Emits the test only if the brackets aren't empty.
+    (@test $name:ident [] $body:block) => {};
+    (@test $name:ident [$($registered:expr)+] $body:block) => {
+        #[cfg(test)]
+        #[test]
+        fn $name() $body
+    };
 }
@@ -0,0 +1,163 @@
+/// Overrides for the parameters a solution declares in `solution!(.., params: { .. })`.
+///
+/// Parameters default to their values for the real input. Examples can override them with a
+/// manifest next to the example file, e.g. `data/examples/18.toml`:
+///
+/// ```toml
+/// [params]
+/// size = 7
+/// bytes = 12
+/// ```
+///
+/// and `cargo solve 18 --param size=7` overrides them on the command line.
+use std::cell::RefCell;
+use std::path::Path;
+use std::{fs, io};
+
+use toml::{Table, Value};
+
+use crate::template::{config, Day};
+
+/// Parameter names and their unparsed values, in the order they were given.
+pub type Overrides = Vec<(String, String)>;
+
+thread_local! {
+    static OVERRIDES: RefCell<Overrides> = const { RefCell::new(vec![]) };
+}
+
+/// The overrides the parameters of a solution are currently read with.
+pub fn overrides() -> Overrides {
+    OVERRIDES.with(|overrides| overrides.borrow().clone())
+}
+
+/// Replaces the overrides for the rest of the thread, used by the solution binaries.
+pub fn set_overrides(overrides: Overrides) {
+    OVERRIDES.with(|current| *current.borrow_mut() = overrides);
+}
+
+/// Runs `f` with `overrides` and restores the previous overrides afterwards, even if `f` panics.
+pub fn with_overrides<T>(overrides: Overrides, f: impl FnOnce() -> T) -> T {
+    struct Restore(Overrides);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            set_overrides(std::mem::take(&mut self.0));
+        }
+    }
+
+    let _restore = Restore(OVERRIDES.with(|current| current.replace(overrides)));
+    f()
+}
+
+/// Reads the `--param name=value` options from the command line.
+pub fn from_args() -> Result<Overrides, String> {
+    let mut args = pico_args::Arguments::from_env();
+    let values: Vec<String> = args.values_from_str("--param").map_err(|e| e.to_string())?;
+    values
+        .iter()
+        .map(|value| match value.split_once('=') {
+            Some((name, value)) if !name.is_empty() => Ok((name.into(), value.into())),
+            _ => Err(format!("`--param` expects `name=value`, got `{value}`")),
+        })
+        .collect()
+}
+
+/// Reads the manifest of an example, e.g. `18.toml` for `18.txt`. An example without a
+/// manifest has no overrides.
+pub fn read_manifest(example: &Path) -> Result<Overrides, String> {
+    let path = example.with_extension("toml");
+    let error = |e: &dyn std::fmt::Display| format!("{}: {e}", path.display());
+    let manifest = match fs::read_to_string(&path) {
+        Ok(manifest) => manifest,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(error(&e)),
+    };
+    let mut root: Table = manifest
+        .parse()
+        .map_err(|e: toml::de::Error| error(&e.message()))?;
+    let params = match root.remove("params") {
+        Some(Value::Table(params)) => params,
+        Some(_) => return Err(error(&"`params` must be a table")),
+        None => Table::new(),
+    };
+    if let Some(key) = root.keys().next() {
+        return Err(error(&format!("unknown key `{key}`")));
+    }
+    params
+        .into_iter()
+        .map(|(name, value)| {
+            let value = match value {
+                Value::String(s) => s,
+                Value::Integer(n) => n.to_string(),
+                Value::Float(n) => n.to_string(),
+                Value::Boolean(b) => b.to_string(),
+                _ => return Err(error(&format!("`params.{name}` must be a single value"))),
+            };
+            Ok((name, value))
+        })
+        .collect()
+}
+
+/// Runs `f` on the example of `day` with the overrides from its manifest.
+///
+/// # Panics
+/// If the example doesn't exist or its manifest is invalid.
+pub fn with_example<T>(day: Day, f: impl FnOnce(&str) -> T) -> T {
+    let path = config::get().paths.example(day);
+    let input = fs::read_to_string(&path).expect("could not open example file");
+    let overrides = read_manifest(&path).unwrap_or_else(|e| panic!("{e}"));
+    with_overrides(overrides, || f(&input))
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+
+    fn overrides(pairs: &[(&str, &str)]) -> Overrides {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn restores_overrides() {
+        set_overrides(overrides(&[("size", "71")]));
+        let inner = with_overrides(overrides(&[("size", "7")]), super::overrides);
+        assert_eq!(inner, overrides(&[("size", "7")]));
+        assert_eq!(super::overrides(), overrides(&[("size", "71")]));
+
+        let panicked = std::panic::catch_unwind(|| {
+            with_overrides(vec![], || panic!("boom"));
+        });
+        assert!(panicked.is_err());
+        assert_eq!(super::overrides(), overrides(&[("size", "71")]));
+        set_overrides(vec![]);
+    }
+
+    #[test]
+    fn reads_manifests() {
+        let dir = std::env::temp_dir().join(format!("aoc-params-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let example = dir.join("18.txt");
+        assert_eq!(read_manifest(&example), Ok(vec![]));
+
+        fs::write(
+            dir.join("18.toml"),
+            "[params]\nsize = 7\nname = \"x\"\nfast = true\n",
+        )
+        .unwrap();
+        let mut params = read_manifest(&example).unwrap();
+        params.sort();
+        assert_eq!(
+            params,
+            overrides(&[("fast", "true"), ("name", "x"), ("size", "7")])
+        );
+
+        fs::write(dir.join("18.toml"), "size = 7\n").unwrap();
+        assert!(read_manifest(&example)
+            .unwrap_err()
+            .ends_with("unknown key `size`"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
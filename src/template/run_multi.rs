@@ -61,14 +61,18 @@ impl From<std::io::Error> for Error {
 
 #[must_use]
 pub fn get_path_for_bin(day: Day) -> String {
-    format!("./src/bin/{day}.rs")
+    crate::template::config::get()
+        .paths
+        .bin(day)
+        .display()
+        .to_string()
 }
 
 /// All solutions live in isolated binaries.
 /// This module encapsulates interaction with these binaries, both invoking them as well as parsing the timing output.
 pub mod child_commands {
     use super::{get_path_for_bin, Error};
-    use crate::template::Day;
+    use crate::template::{Day, ANSI_BOLD, ANSI_RESET};
     use std::{
         io::{BufRead, BufReader},
         path::Path,
@@ -164,6 +168,52 @@ pub mod child_commands {
         timings
     }
 
+    /// Reads the results of both parts from the output of a solution binary.
+    pub fn parse_results(output: &str) -> [Option<String>; 2] {
+        let mut results = [None, None];
+        let lines = output.lines().map(|line| {
+            line.rsplit('\r')
+                .next()
+                .unwrap()
+                .replace(ANSI_BOLD, "")
+                .replace(ANSI_RESET, "")
+        });
+        // the lines of a multi-line result follow its part's `▼` line.
+        let mut multiline: Option<usize> = None;
+        for line in lines {
+            let Some((part, rest)) = line
+                .strip_prefix("Part ")
+                .and_then(|line| line.split_once(": "))
+            else {
+                if let Some(i) = multiline {
+                    let result: &mut String = results[i].get_or_insert_default();
+                    if !result.is_empty() {
+                        result.push('\n');
+                    }
+                    result.push_str(&line);
+                }
+                continue;
+            };
+            let Some(i) = part.parse::<usize>().ok().filter(|p| (1..=2).contains(p)) else {
+                continue;
+            };
+            let rest = rest.trim_end();
+            multiline = rest.starts_with('▼').then_some(i - 1);
+            results[i - 1] = if rest.starts_with('✖') {
+                None
+            } else if multiline.is_some() {
+                Some(String::new())
+            } else {
+                Some(
+                    rest.rsplit_once(" (")
+                        .map_or(rest, |(result, _)| result)
+                        .into(),
+                )
+            };
+        }
+        results
+    }
+
     fn parse_to_float(s: &str, postfix: &str) -> Option<f64> {
         s.split(postfix).next()?.parse().ok()
     }
@@ -174,7 +224,7 @@ pub mod child_commands {
             .split(" samples)")
             .next()?
             .split('(')
-            .last()?
+            .next_back()?
             .split('@')
             .next()?
             .trim();
@@ -205,7 +255,7 @@ pub mod child_commands {
 
     #[cfg(feature = "test_lib")]
     mod tests {
-        use super::parse_exec_time;
+        use super::{parse_exec_time, parse_results};
 
         use crate::day;
 
@@ -253,5 +303,17 @@ pub mod child_commands {
             assert_eq!(res.part_1.is_none(), true);
             assert_eq!(res.part_2.is_none(), true);
         }
+
+        #[test]
+        fn parses_results() {
+            let output = "Part 1: \x1b[1m42\x1b[0m (1.2ms)\ndebug output\nPart 2: ✖             \n";
+            assert_eq!(parse_results(output), [Some("42".into()), None]);
+
+            let output = "Part 1: ▼ (3.0µs)\n#.#\n.#.\n\rPart 2: \x1b[1m7 (a)\x1b[0m (10ns)\n";
+            assert_eq!(
+                parse_results(output),
+                [Some("#.#\n.#.".into()), Some("7 (a)".into())]
+            );
+        }
     }
 }
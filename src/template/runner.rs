@@ -2,12 +2,13 @@
 use std::fmt::Display;
 use std::hint::black_box;
 use std::io::{stdout, Write};
-use std::process::Output;
 use std::time::{Duration, Instant};
 use std::{cmp, env, process};
 
+use crate::template::answers::Answers;
+use crate::template::aoc_cli::{AocCommandError, SubmissionOutcome};
 use crate::template::ANSI_BOLD;
-use crate::template::{aoc_cli, Day, ANSI_ITALIC, ANSI_RESET};
+use crate::template::{aoc_cli, config, Day, ANSI_ITALIC, ANSI_RESET};
 
 pub fn run_part<I: Copy, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
     let part_str = format!("Part {part}");
@@ -18,7 +19,10 @@ pub fn run_part<I: Copy, T: Display>(func: impl Fn(I) -> Option<T>, input: I, da
     print_result(&result, &part_str, &format_duration(&duration, samples));
 
     if let Some(result) = result {
-        submit_result(result, day, part);
+        let result = result.to_string();
+        if let Some(outcome) = submit_result(&result, day, part) {
+            handle_submission(outcome, &result, day, part);
+        }
     }
 }
 
@@ -128,11 +132,11 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
 /// Parse the arguments passed to `solve` and try to submit one part of the solution if:
 ///  1. we are in `--release` mode.
 ///  2. aoc-cli is installed.
-fn submit_result<T: Display>(
-    result: T,
+fn submit_result(
+    result: &str,
     day: Day,
     part: u8,
-) -> Option<Result<Output, aoc_cli::AocCommandError>> {
+) -> Option<Result<SubmissionOutcome, AocCommandError>> {
     let args: Vec<String> = env::args().collect();
 
     if !args.contains(&"--submit".into()) {
@@ -161,5 +165,43 @@ fn submit_result<T: Display>(
     }
 
     println!("Submitting result via aoc-cli...");
-    Some(aoc_cli::submit(day, part, &result.to_string()))
+    Some(aoc_cli::submit(day, part, result))
+}
+
+/// Prints the outcome of a submission. A correct answer is recorded in the known answers, and
+/// solving part one downloads the puzzle again so the description of part two is available.
+fn handle_submission(
+    outcome: Result<SubmissionOutcome, AocCommandError>,
+    result: &str,
+    day: Day,
+    part: u8,
+) {
+    let outcome = match outcome {
+        Ok(outcome) => outcome,
+        Err(e) => {
+            eprintln!("Could not submit the result: {e}");
+            process::exit(1);
+        }
+    };
+    println!("{ANSI_BOLD}{outcome}{ANSI_RESET}");
+    if outcome != SubmissionOutcome::Correct {
+        return;
+    }
+
+    let mut answers = Answers::read_from_file();
+    answers.set(day, part, result);
+    match answers.store_file() {
+        Ok(()) => println!(
+            "Recorded the answer in \"{}\".",
+            config::get().paths.answers.display()
+        ),
+        Err(e) => eprintln!("Could not record the answer: {e}"),
+    }
+
+    if part == 1 {
+        println!("Downloading the puzzle description of part two...");
+        if let Err(e) = aoc_cli::download_puzzle(day, config::get().year) {
+            eprintln!("Could not download the puzzle: {e}");
+        }
+    }
 }
@@ -0,0 +1,285 @@
+/// An encrypted archive of the puzzle inputs and descriptions. It can be committed to git in
+/// place of the plaintext files, which must not be published.
+///
+/// The archive starts with [`MAGIC`], followed by a random salt and nonce. The files are
+/// encrypted with ChaCha20-Poly1305, using a key derived from the passphrase with Argon2id.
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::process::Command;
+use std::{fs, io};
+
+use argon2::Argon2;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+
+use crate::template::config;
+
+const MAGIC: &[u8; 8] = b"AOCSEAL1";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// The data folders that are sealed, with the extension of their files.
+const FOLDERS: [(&str, &str); 2] = [("inputs", "txt"), ("puzzles", "md")];
+
+#[derive(Debug)]
+pub enum SealError {
+    IO(io::Error),
+    /// The archive is truncated or not an archive at all.
+    Format,
+    /// The passphrase is wrong or the archive was modified.
+    Decrypt,
+    KeyDerivation(String),
+}
+
+impl Display for SealError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SealError::IO(e) => write!(f, "{e}"),
+            SealError::Format => write!(f, "not a sealed inputs archive."),
+            SealError::Decrypt => write!(
+                f,
+                "could not decrypt, the passphrase is wrong or the archive is corrupted."
+            ),
+            SealError::KeyDerivation(e) => write!(f, "could not derive a key: {e}"),
+        }
+    }
+}
+
+impl From<io::Error> for SealError {
+    fn from(e: io::Error) -> Self {
+        SealError::IO(e)
+    }
+}
+
+/// A file in the archive, e.g. `01.txt` in `inputs`.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Entry {
+    pub folder: String,
+    pub name: String,
+    pub contents: Vec<u8>,
+}
+
+impl Entry {
+    /// Where the file belongs according to the configured data folders.
+    pub fn path(&self) -> PathBuf {
+        config::get().paths.folder(&self.folder).join(&self.name)
+    }
+
+    /// Only plain file names in a known folder, so an archive can't write anywhere else.
+    fn is_valid(&self) -> bool {
+        FOLDERS.iter().any(|(folder, _)| *folder == self.folder)
+            && !self.name.is_empty()
+            && !self.name.starts_with('.')
+            && !self.name.contains(['/', '\\'])
+    }
+}
+
+fn derive_key(passphrase: &[u8], salt: &[u8]) -> Result<Key, SealError> {
+    let mut key = Key::default();
+    Argon2::default()
+        .hash_password_into(passphrase, salt, &mut key)
+        .map_err(|e| SealError::KeyDerivation(e.to_string()))?;
+    Ok(key)
+}
+
+/// Every field is stored as its length followed by its bytes.
+fn encode(entries: &[Entry]) -> Vec<u8> {
+    let mut bytes = vec![];
+    for entry in entries {
+        for field in [
+            entry.folder.as_bytes(),
+            entry.name.as_bytes(),
+            &entry.contents,
+        ] {
+            bytes.extend((field.len() as u64).to_le_bytes());
+            bytes.extend(field);
+        }
+    }
+    bytes
+}
+
+fn take_field(bytes: &mut &[u8]) -> Result<Vec<u8>, SealError> {
+    let (len, rest) = bytes.split_first_chunk::<8>().ok_or(SealError::Format)?;
+    let len = usize::try_from(u64::from_le_bytes(*len)).map_err(|_| SealError::Format)?;
+    let (field, rest) = rest.split_at_checked(len).ok_or(SealError::Format)?;
+    *bytes = rest;
+    Ok(field.to_vec())
+}
+
+fn decode(mut bytes: &[u8]) -> Result<Vec<Entry>, SealError> {
+    let mut entries = vec![];
+    while !bytes.is_empty() {
+        let mut text = || String::from_utf8(take_field(&mut bytes)?).map_err(|_| SealError::Format);
+        let entry = Entry {
+            folder: text()?,
+            name: text()?,
+            contents: take_field(&mut bytes)?,
+        };
+        if !entry.is_valid() {
+            return Err(SealError::Format);
+        }
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+/// Encrypts `entries` with a fresh salt and nonce.
+pub fn seal(entries: &[Entry], passphrase: &[u8]) -> Result<Vec<u8>, SealError> {
+    let mut salt = [0; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, &salt)?);
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let payload = Payload {
+        msg: &encode(entries),
+        aad: MAGIC,
+    };
+    let ciphertext = cipher
+        .encrypt(&nonce, payload)
+        .map_err(|_| SealError::Decrypt)?;
+
+    let mut archive = MAGIC.to_vec();
+    archive.extend(salt);
+    archive.extend(nonce);
+    archive.extend(ciphertext);
+    Ok(archive)
+}
+
+pub fn unseal(archive: &[u8], passphrase: &[u8]) -> Result<Vec<Entry>, SealError> {
+    let rest = archive.strip_prefix(MAGIC).ok_or(SealError::Format)?;
+    let (salt, rest) = rest.split_at_checked(SALT_LEN).ok_or(SealError::Format)?;
+    let (nonce, ciphertext) = rest.split_at_checked(NONCE_LEN).ok_or(SealError::Format)?;
+    let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, salt)?);
+    let payload = Payload {
+        msg: ciphertext,
+        aad: MAGIC,
+    };
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(nonce), payload)
+        .map_err(|_| SealError::Decrypt)?;
+    decode(&plaintext)
+}
+
+/// Reads the non-empty inputs and puzzle descriptions from the data folders, sorted by folder
+/// and name.
+pub fn collect() -> io::Result<Vec<Entry>> {
+    let mut entries = vec![];
+    for (folder, extension) in FOLDERS {
+        let Ok(files) = fs::read_dir(config::get().paths.folder(folder)) else {
+            continue;
+        };
+        for file in files {
+            let path = file?.path();
+            let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            let entry = Entry {
+                folder: folder.into(),
+                name: name.into(),
+                contents: fs::read(&path)?,
+            };
+            if path.extension().is_some_and(|e| e == extension)
+                && entry.is_valid()
+                && !entry.contents.is_empty()
+            {
+                entries.push(entry);
+            }
+        }
+    }
+    entries.sort();
+    Ok(entries)
+}
+
+/// The plaintext inputs and puzzle descriptions that git tracks. Empty if git isn't available
+/// or this isn't a repository.
+pub fn tracked_plaintext() -> Vec<String> {
+    let paths = &config::get().paths;
+    let output = Command::new("git")
+        .args(["ls-files", "-z", "--"])
+        .args(FOLDERS.map(|(folder, _)| paths.folder(folder)))
+        .output()
+        .ok()
+        .filter(|output| output.status.success());
+    let Some(output) = output else {
+        return vec![];
+    };
+    String::from_utf8_lossy(&output.stdout)
+        .split('\0')
+        .filter(|file| {
+            FOLDERS
+                .iter()
+                .any(|(_, extension)| file.ends_with(&format!(".{extension}")))
+        })
+        .map(String::from)
+        .collect()
+}
+
+/// Prints a warning if git tracks plaintext inputs, and returns whether it does.
+pub fn warn_if_tracked() -> bool {
+    let tracked = tracked_plaintext();
+    if tracked.is_empty() {
+        return false;
+    }
+    eprintln!(
+        "Warning: git tracks {} plaintext input or puzzle file(s), which must not be published:",
+        tracked.len()
+    );
+    for file in &tracked {
+        eprintln!("  {file}");
+    }
+    eprintln!("Remove them with `git rm --cached <file>` and commit the sealed archive instead.");
+    true
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+
+    fn entry(folder: &str, name: &str, contents: &str) -> Entry {
+        Entry {
+            folder: folder.into(),
+            name: name.into(),
+            contents: contents.into(),
+        }
+    }
+
+    #[test]
+    fn round_trips() {
+        let entries = vec![
+            entry("inputs", "01.txt", "3   4\n4   3\n"),
+            entry("puzzles", "01.md", "# Day 1"),
+        ];
+        let archive = seal(&entries, b"secret").unwrap();
+        assert!(!archive.windows(5).any(|w| w == b"Day 1"));
+        assert_eq!(unseal(&archive, b"secret").unwrap(), entries);
+        assert!(matches!(
+            unseal(&archive, b"wrong"),
+            Err(SealError::Decrypt)
+        ));
+
+        let mut tampered = archive.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(matches!(
+            unseal(&tampered, b"secret"),
+            Err(SealError::Decrypt)
+        ));
+        assert!(matches!(
+            unseal(b"AOCSEAL1", b"secret"),
+            Err(SealError::Format)
+        ));
+    }
+
+    #[test]
+    fn rejects_unsafe_names() {
+        for entry in [
+            entry("inputs", "../01.txt", ""),
+            entry("inputs", ".keep", ""),
+            entry("src", "main.rs", ""),
+        ] {
+            assert!(matches!(decode(&encode(&[entry])), Err(SealError::Format)));
+        }
+        let mut truncated = encode(&[entry("inputs", "01.txt", "1")]);
+        truncated.pop();
+        assert!(matches!(decode(&truncated), Err(SealError::Format)));
+    }
+}
@@ -0,0 +1,303 @@
+/// Runs solutions on randomly generated inputs and keeps the inputs that make them panic.
+use std::any::Any;
+use std::fmt::Display;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::{fs, process, thread};
+
+use crate::template::{config, Day, ANSI_BOLD, ANSI_RESET};
+
+/// Creates a puzzle input from a seed. `size` is a hint for how large the input should be.
+pub type Generator = fn(u64, usize) -> String;
+
+const DEFAULT_COUNT: u64 = 100;
+const DEFAULT_SIZE: usize = 10;
+
+/// A small, seedable random number generator (SplitMix64) for input generators.
+#[derive(Clone, Debug)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `range`, which must not be empty.
+    pub fn range(&mut self, range: std::ops::Range<usize>) -> usize {
+        assert!(!range.is_empty(), "cannot pick from an empty range");
+        range.start + (self.next_u64() % (range.end - range.start) as u64) as usize
+    }
+
+    /// `true` with probability `p`.
+    pub fn chance(&mut self, p: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < p
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.range(0..items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.range(0..i + 1));
+        }
+    }
+}
+
+type Solve = dyn Fn(&str) -> Option<String>;
+
+/// A part of a solution, or an alternate implementation of it, with its result erased to a
+/// string.
+pub struct Part {
+    pub(crate) part: u8,
+    pub(crate) name: &'static str,
+    solve: Box<Solve>,
+}
+
+impl Part {
+    pub fn new<T: Display>(
+        part: u8,
+        name: &'static str,
+        func: impl Fn(&str) -> Option<T> + 'static,
+    ) -> Self {
+        Self {
+            part,
+            name,
+            solve: Box::new(move |input| func(input).map(|r| r.to_string())),
+        }
+    }
+}
+
+type PanicHook = Box<dyn Fn(&panic::PanicHookInfo<'_>) + Sync + Send>;
+
+/// Held while [`with_quiet_panics`] replaces the panic hook, which is shared by the whole
+/// process, e.g. by tests running in parallel.
+static QUIET_PANICS: Mutex<()> = Mutex::new(());
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".into())
+}
+
+/// Runs `part` on `input`, returning the panic message if it panics.
+pub(crate) fn catch_panic(part: &Part, input: &str) -> Result<Option<String>, String> {
+    panic::catch_unwind(AssertUnwindSafe(|| (part.solve)(input)))
+        .map_err(|payload| panic_message(payload.as_ref()))
+}
+
+/// Runs `f` without printing the panics of the current thread, so [`catch_panic`] can report
+/// them instead. Panics of other threads are still printed.
+pub(crate) fn with_quiet_panics<T>(f: impl FnOnce() -> T) -> T {
+    let _lock = QUIET_PANICS.lock().unwrap_or_else(PoisonError::into_inner);
+    let previous: Arc<PanicHook> = Arc::new(panic::take_hook());
+    let forward = Arc::clone(&previous);
+    let quiet_thread = thread::current().id();
+    panic::set_hook(Box::new(move |info| {
+        if thread::current().id() != quiet_thread {
+            forward(info);
+        }
+    }));
+
+    // the hook can't be replaced while unwinding, so a panic of `f` is caught until it's restored.
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    // dropping the quiet hook releases its reference to the previous one.
+    drop(panic::take_hook());
+    match Arc::try_unwrap(previous) {
+        Ok(previous) => panic::set_hook(previous),
+        Err(previous) => panic::set_hook(Box::new(move |info| previous(info))),
+    }
+    result.unwrap_or_else(|payload| panic!("{}", panic_message(payload.as_ref())))
+}
+
+fn regression_path(day: Day, seed: u64) -> std::path::PathBuf {
+    config::get()
+        .paths
+        .examples
+        .join(format!("{day}-stress-{seed}.txt"))
+}
+
+/// The `--seed`, `--count` and `--size` options for generating inputs.
+pub(crate) struct Options {
+    pub seed: u64,
+    pub count: u64,
+    pub size: usize,
+}
+
+impl Options {
+    /// Reads the options from the command line, exiting on invalid values.
+    pub fn from_args() -> Self {
+        let mut args = pico_args::Arguments::from_env();
+        let options = (|| -> Result<_, pico_args::Error> {
+            Ok((
+                args.opt_value_from_str("--seed")?,
+                args.opt_value_from_str("--count")?,
+                args.opt_value_from_str("--size")?,
+            ))
+        })();
+        let (seed, count, size) = match options {
+            Ok(options) => options,
+            Err(e) => {
+                eprintln!("Error: {e}");
+                process::exit(1);
+            }
+        };
+        let seed = seed.unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs()
+        });
+        Self {
+            seed,
+            count: count.unwrap_or(DEFAULT_COUNT),
+            size: size.unwrap_or(DEFAULT_SIZE),
+        }
+    }
+}
+
+/// Entry point for `cargo stress <day>`, reads `--seed`, `--count` and `--size` from the
+/// command line.
+pub fn run(day: Day, generate: Generator, parts: &[Part]) {
+    let Options { seed, count, size } = Options::from_args();
+
+    println!(
+        "Day {day}: {count} inputs of size {size}, seeds {seed}..{}",
+        seed + count
+    );
+
+    let failures = with_quiet_panics(|| {
+        let mut failures = 0;
+        for seed in seed..seed + count {
+            let input = generate(seed, size);
+            let panics = parts
+                .iter()
+                .filter_map(|part| Some((part.part, catch_panic(part, &input).err()?)))
+                .collect::<Vec<_>>();
+            if panics.is_empty() {
+                continue;
+            }
+            failures += 1;
+            let path = regression_path(day, seed);
+            if !path.exists() {
+                fs::write(&path, &input).expect("could not write regression file");
+            }
+            println!(
+                "{ANSI_BOLD}seed {seed}{ANSI_RESET}, saved to {}",
+                path.display()
+            );
+            for (part, message) in panics {
+                println!("  Part {part} panicked: {message}");
+            }
+        }
+        failures
+    });
+
+    if failures > 0 {
+        println!("{failures} of {count} inputs failed.");
+        process::exit(1);
+    }
+    println!("All inputs passed.");
+}
+
+/// Runs every part on the inputs saved by [`run`] and panics if any of them still fails.
+pub fn check_regressions(day: Day, parts: &[Part]) {
+    let prefix = format!("{day}-stress-");
+    let Ok(entries) = fs::read_dir(&config::get().paths.examples) else {
+        return;
+    };
+    let mut files = entries
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(&prefix) && name.ends_with(".txt"))
+        })
+        .collect::<Vec<_>>();
+    files.sort();
+
+    let failures = with_quiet_panics(|| {
+        let mut failures = vec![];
+        for path in &files {
+            let input = fs::read_to_string(path).unwrap();
+            for part in parts {
+                if let Err(message) = catch_panic(part, &input) {
+                    failures.push(format!(
+                        "{} part {} panicked: {message}",
+                        path.display(),
+                        part.part
+                    ));
+                }
+            }
+        }
+        failures
+    });
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rng_is_deterministic() {
+        let a: Vec<_> = (0..5)
+            .scan(Rng::new(42), |rng, _| Some(rng.next_u64()))
+            .collect();
+        let b: Vec<_> = (0..5)
+            .scan(Rng::new(42), |rng, _| Some(rng.next_u64()))
+            .collect();
+        assert_eq!(a, b);
+        assert_ne!(Rng::new(1).next_u64(), Rng::new(2).next_u64());
+    }
+
+    #[test]
+    fn rng_stays_in_range() {
+        let mut rng = Rng::new(7);
+        let mut seen = [false; 5];
+        for _ in 0..1000 {
+            let n = rng.range(10..15);
+            assert!((10..15).contains(&n));
+            seen[n - 10] = true;
+        }
+        assert!(seen.iter().all(|s| *s));
+
+        let mut items: Vec<_> = (0..20).collect();
+        rng.shuffle(&mut items);
+        items.sort();
+        assert_eq!(items, (0..20).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn catches_panics() {
+        let ok = Part::new(1, "ok", |input: &str| Some(input.len()));
+        let panics = Part::new(2, "panics", |_: &str| -> Option<u32> { panic!("boom") });
+        let (ok, panics) =
+            with_quiet_panics(|| (catch_panic(&ok, "abc"), catch_panic(&panics, "")));
+        assert_eq!(ok, Ok(Some("3".to_string())));
+        assert_eq!(panics, Err("boom".to_string()));
+    }
+
+    #[test]
+    fn restores_the_panic_hook() {
+        let _ = panic::catch_unwind(|| with_quiet_panics(|| panic!("boom")));
+        let quiet = with_quiet_panics(|| {
+            let part = Part::new(1, "panics", |_: &str| -> Option<u32> {
+                panic!("{}", "formatted")
+            });
+            catch_panic(&part, "")
+        });
+        assert_eq!(quiet, Err("formatted".to_string()));
+    }
+}
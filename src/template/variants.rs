@@ -0,0 +1,236 @@
+/// Compares alternate implementations of a part with the solution on the same inputs.
+use std::fmt::{self, Display};
+use std::path::Path;
+use std::{fs, process};
+
+use crate::template::params::{self, Overrides};
+use crate::template::stress::{catch_panic, with_quiet_panics, Generator, Options, Part};
+use crate::template::{config, Day, ANSI_BOLD, ANSI_RESET};
+
+/// The number of generated inputs `cargo test` compares the variants on.
+const TEST_COUNT: u64 = 20;
+const TEST_SIZE: usize = 10;
+/// Longer inputs are cut off when printing a difference.
+const MAX_PRINTED_LINES: usize = 40;
+
+struct Input {
+    source: String,
+    text: String,
+    /// The parameter overrides to run the parts with.
+    params: Overrides,
+}
+
+/// Reads the inputs for `day` in `folder`, with the current parameter overrides.
+fn read_inputs(folder: &Path, day: Day) -> Vec<Input> {
+    let Ok(entries) = fs::read_dir(folder) else {
+        return vec![];
+    };
+    let mut paths = entries
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_suffix(".txt"))
+                .is_some_and(|name| name == day.to_string() || name.starts_with(&format!("{day}-")))
+        })
+        .collect::<Vec<_>>();
+    paths.sort();
+    paths
+        .into_iter()
+        .map(|path| Input {
+            source: path.display().to_string(),
+            text: fs::read_to_string(&path).unwrap(),
+            params: params::overrides(),
+        })
+        .collect()
+}
+
+/// The examples with the overrides from their manifests, the real input if it exists, and
+/// `count` generated inputs.
+fn inputs(
+    day: Day,
+    generator: Option<Generator>,
+    seed: u64,
+    count: u64,
+    size: usize,
+) -> impl Iterator<Item = Input> {
+    let paths = &config::get().paths;
+    let examples = read_inputs(&paths.examples, day)
+        .into_iter()
+        .map(|input| Input {
+            params: params::read_manifest(Path::new(&input.source))
+                .unwrap_or_else(|e| panic!("{e}")),
+            ..input
+        });
+    let real = read_inputs(&paths.inputs, day)
+        .into_iter()
+        .filter(move |input| input.source.ends_with(&format!("{day}.txt")));
+    let generated = generator.into_iter().flat_map(move |generate| {
+        (seed..seed + count).map(move |seed| Input {
+            source: format!("seed {seed} (size {size})"),
+            text: generate(seed, size),
+            params: params::overrides(),
+        })
+    });
+    examples.chain(real).chain(generated)
+}
+
+struct Difference {
+    input: Input,
+    part: u8,
+    results: Vec<(&'static str, Result<Option<String>, String>)>,
+}
+
+impl Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{ANSI_BOLD}Part {} differs on {}{ANSI_RESET}",
+            self.part, self.input.source
+        )?;
+        let width = self.results.iter().map(|(name, _)| name.len()).max();
+        for (name, result) in &self.results {
+            let result = match result {
+                Ok(Some(result)) => result.clone(),
+                Ok(None) => "✖".into(),
+                Err(message) => format!("panicked: {message}"),
+            };
+            writeln!(f, "  {name:<width$}  {result}", width = width.unwrap_or(0))?;
+        }
+        writeln!(f, "Input:")?;
+        let lines = self.input.text.lines().count();
+        for line in self.input.text.lines().take(MAX_PRINTED_LINES) {
+            writeln!(f, "  {line}")?;
+        }
+        if lines > MAX_PRINTED_LINES {
+            writeln!(f, "  ... ({} more lines)", lines - MAX_PRINTED_LINES)?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs every part that has variants and all of its variants on `input`.
+fn compare(input: Input, parts: &[Part], variants: &[Part]) -> Option<Difference> {
+    for part in parts {
+        let mut implementations = variants.iter().filter(|v| v.part == part.part).peekable();
+        if implementations.peek().is_none() {
+            continue;
+        }
+        let results = [part]
+            .into_iter()
+            .chain(implementations)
+            .map(|p| {
+                let result =
+                    params::with_overrides(input.params.clone(), || catch_panic(p, &input.text));
+                (p.name, result)
+            })
+            .collect::<Vec<_>>();
+        let agree = results.iter().all(|(_, r)| r.is_ok() && *r == results[0].1);
+        if !agree {
+            return Some(Difference {
+                input,
+                part: part.part,
+                results,
+            });
+        }
+    }
+    None
+}
+
+/// Entry point for `cargo solve <day> --compare`, reads `--seed`, `--count` and `--size` from
+/// the command line.
+pub fn run(day: Day, parts: &[Part], variants: &[Part], generator: Option<Generator>) {
+    if variants.is_empty() {
+        eprintln!("Day {day} has no alternate implementations.");
+        process::exit(1);
+    }
+    let Options { seed, count, size } = Options::from_args();
+    let names = variants.iter().map(|v| v.name).collect::<Vec<_>>();
+    println!("Day {day}: comparing {}", names.join(", "));
+    if generator.is_some() {
+        println!(
+            "Generating {count} inputs of size {size}, seeds {seed}..{}",
+            seed + count
+        );
+    }
+
+    let mut compared = 0;
+    let difference = with_quiet_panics(|| {
+        inputs(day, generator, seed, count, size).find_map(|input| {
+            compared += 1;
+            compare(input, parts, variants)
+        })
+    });
+    match difference {
+        Some(difference) => {
+            print!("{difference}");
+            process::exit(1);
+        }
+        None => println!("All variants agree on {compared} inputs."),
+    }
+}
+
+/// Compares the variants on the examples, the real input and a few generated inputs, and panics
+/// on the first difference.
+pub fn check(day: Day, parts: &[Part], variants: &[Part], generator: Option<Generator>) {
+    let difference = with_quiet_panics(|| {
+        inputs(day, generator, 0, TEST_COUNT, TEST_SIZE)
+            .find_map(|input| compare(input, parts, variants))
+    });
+    if let Some(difference) = difference {
+        panic!("{difference}");
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+
+    fn input(text: &str) -> Input {
+        Input {
+            source: "test".into(),
+            text: text.into(),
+            params: vec![],
+        }
+    }
+
+    #[test]
+    fn finds_differences() {
+        let parts = [
+            Part::new(1, "length", |input: &str| Some(input.len())),
+            Part::new(2, "lines", |input: &str| Some(input.lines().count())),
+        ];
+        let variants = [
+            Part::new(1, "chars", |input: &str| Some(input.chars().count())),
+            Part::new(2, "newlines", |input: &str| {
+                Some(input.matches('\n').count() + 1)
+            }),
+        ];
+        assert!(compare(input("abc\ndef"), &parts, &variants).is_none());
+
+        let difference = compare(input("äbc"), &parts, &variants).unwrap();
+        assert_eq!(difference.part, 1);
+        assert_eq!(
+            difference.results,
+            vec![
+                ("length", Ok(Some("4".into()))),
+                ("chars", Ok(Some("3".into())))
+            ]
+        );
+
+        let difference = compare(input("abc\n"), &parts, &variants).unwrap();
+        assert_eq!(difference.part, 2);
+    }
+
+    #[test]
+    fn reports_panics() {
+        let parts = [Part::new(1, "panics", |_: &str| -> Option<u32> {
+            panic!("boom")
+        })];
+        let variants = [Part::new(1, "also panics", |_: &str| -> Option<u32> {
+            panic!("boom")
+        })];
+        let difference = with_quiet_panics(|| compare(input(""), &parts, &variants));
+        assert!(difference.is_some());
+    }
+}